@@ -0,0 +1,156 @@
+#include "CanCoderContainer.h"
+#include "Errors.h"
+#include "Timestamps.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> CanCoderContainer::HandleCommand(uint8_t can_id, const cancoder_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case cancoder_ffi::CommandType::Create:
+		{
+			HandleCreate(can_id, (const cancoder_ffi::config::CanCoderConfig *)command->data);
+			break;
+		}
+		case cancoder_ffi::CommandType::SetPosition:
+		{
+			HandleSetPosition(can_id, *(const double *)command->data);
+			break;
+		}
+		case cancoder_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(can_id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void CanCoderContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[can_id, encoder] : m_encoders)
+	{
+		auto &position = encoder->GetPosition();
+		cancoder_ffi::Data *data = (cancoder_ffi::Data *)malloc(sizeof(cancoder_ffi::Data));
+
+		*data = cancoder_ffi::Data{
+			.connected = encoder->IsConnected(),
+			.position = position.GetValueAsDouble(),
+			.absolute_position = encoder->GetAbsolutePosition().GetValueAsDouble(),
+			.velocity = encoder->GetVelocity().GetValueAsDouble(),
+			.magnet_health = Convert(encoder->GetMagnetHealth().GetValue()),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::CanCoder,
+				.bus = ffi::device::Bus::Can,
+				.id = can_id,
+			},
+			.data = data,
+			.timestamp = timestamps::FromSignal(position, now),
+		});
+	}
+}
+
+void CanCoderContainer::HandleCreate(uint8_t can_id, const cancoder_ffi::config::CanCoderConfig *config)
+{
+	if (m_encoders.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("CANcoder (id {}) already exists", can_id).c_str()),
+		};
+	}
+
+	std::unique_ptr<hardware::CANcoder> encoder = std::make_unique<hardware::CANcoder>(can_id);
+	ctre::phoenix::StatusCode status = encoder->GetConfigurator().Apply(Convert(config));
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadConfig, fmt::format("Failed to configure CANcoder (id {})", can_id));
+	}
+
+	m_encoders.emplace(can_id, std::move(encoder));
+}
+
+void CanCoderContainer::HandleSetPosition(uint8_t can_id, double position)
+{
+	ctre::phoenix::StatusCode status = Get(can_id)->SetPosition(units::turn_t{position});
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadCommand, fmt::format("CANcoder (id {}) rejected position", can_id));
+	}
+}
+
+hardware::CANcoder *CanCoderContainer::Get(uint8_t can_id)
+{
+	if (!m_encoders.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("CANcoder (id {}) does not exist", can_id).c_str()),
+		};
+	}
+
+	return m_encoders.at(can_id).get();
+}
+
+configs::CANcoderConfiguration CanCoderContainer::Convert(const cancoder_ffi::config::CanCoderConfig *config)
+{
+	configs::CANcoderConfiguration converted{};
+
+	converted.MagnetSensor.MagnetOffset = units::turn_t{config->magnet_offset};
+	converted.MagnetSensor.AbsoluteSensorDiscontinuityPoint = units::turn_t{config->discontinuity_point};
+	converted.MagnetSensor.SensorDirection = config->sensor_direction == cancoder_ffi::config::SensorDirection::ClockwisePositive
+		? signals::SensorDirectionValue::Clockwise_Positive
+		: signals::SensorDirectionValue::CounterClockwise_Positive;
+
+	return converted;
+}
+
+cancoder_ffi::MagnetHealth CanCoderContainer::Convert(signals::MagnetHealthValue health)
+{
+	if (health == signals::MagnetHealthValue::Magnet_Green)
+	{
+		return cancoder_ffi::MagnetHealth::Green;
+	}
+	else if (health == signals::MagnetHealthValue::Magnet_Orange)
+	{
+		return cancoder_ffi::MagnetHealth::Orange;
+	}
+	else if (health == signals::MagnetHealthValue::Magnet_Red)
+	{
+		return cancoder_ffi::MagnetHealth::Red;
+	}
+
+	return cancoder_ffi::MagnetHealth::Invalid;
+}
+
+void CanCoderContainer::HandleDestroy(uint8_t can_id)
+{
+	if (m_encoders.erase(can_id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("CANcoder (id {}) does not exist", can_id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,212 @@
+#include "IoContainer.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> IoContainer::HandleCommand(const ffi::device::Device &device, const io_ffi::Command *command)
+{
+	uint8_t channel = device.id;
+
+	try
+	{
+		switch (command->kind)
+		{
+		case io_ffi::CommandType::CreateDigitalInput:
+		{
+			EnsureDioVacant(channel);
+			m_digitalInputs.emplace(channel, std::make_unique<frc::DigitalInput>(channel));
+			break;
+		}
+		case io_ffi::CommandType::CreateDigitalOutput:
+		{
+			EnsureDioVacant(channel);
+			m_digitalOutputs.emplace(channel, std::make_unique<frc::DigitalOutput>(channel));
+			break;
+		}
+		case io_ffi::CommandType::CreateAnalogInput:
+		{
+			HandleCreateAnalogInput(channel, (const io_ffi::config::AnalogInputConfig *)command->data);
+			break;
+		}
+		case io_ffi::CommandType::CreateDutyCycleEncoder:
+		{
+			HandleCreateDutyCycleEncoder(channel, (const io_ffi::config::DutyCycleEncoderConfig *)command->data);
+			break;
+		}
+		case io_ffi::CommandType::Set:
+		{
+			if (!m_digitalOutputs.contains(channel))
+			{
+				throw ffi::device::Error{
+					.kind = ffi::device::ErrorType::DeviceNotFound,
+					.message = strdup(fmt::format("Digital output (channel {}) does not exist", channel).c_str()),
+				};
+			}
+
+			m_digitalOutputs.at(channel)->Set(*(const bool *)command->data);
+			break;
+		}
+		case io_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(device);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void IoContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[channel, input] : m_digitalInputs)
+	{
+		io_ffi::DigitalData *data = (io_ffi::DigitalData *)malloc(sizeof(io_ffi::DigitalData));
+		*data = io_ffi::DigitalData{.value = input->Get()};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::DigitalInput,
+				.bus = ffi::device::Bus::Dio,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[channel, output] : m_digitalOutputs)
+	{
+		io_ffi::DigitalData *data = (io_ffi::DigitalData *)malloc(sizeof(io_ffi::DigitalData));
+		*data = io_ffi::DigitalData{.value = output->Get()};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::DigitalOutput,
+				.bus = ffi::device::Bus::Dio,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[channel, input] : m_analogInputs)
+	{
+		io_ffi::AnalogData *data = (io_ffi::AnalogData *)malloc(sizeof(io_ffi::AnalogData));
+
+		*data = io_ffi::AnalogData{
+			.voltage = input->GetVoltage(),
+			.average_voltage = input->GetAverageVoltage(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::AnalogInput,
+				.bus = ffi::device::Bus::Analog,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[channel, encoder] : m_dutyCycleEncoders)
+	{
+		io_ffi::DutyCycleData *data = (io_ffi::DutyCycleData *)malloc(sizeof(io_ffi::DutyCycleData));
+
+		*data = io_ffi::DutyCycleData{
+			.connected = encoder->IsConnected(),
+			.position = encoder->Get(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::DutyCycleEncoder,
+				.bus = ffi::device::Bus::Dio,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+}
+
+void IoContainer::HandleCreateAnalogInput(uint8_t channel, const io_ffi::config::AnalogInputConfig *config)
+{
+	if (m_analogInputs.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Analog channel {} is already in use", channel).c_str()),
+		};
+	}
+
+	std::unique_ptr<frc::AnalogInput> input = std::make_unique<frc::AnalogInput>(channel);
+	input->SetAverageBits(config->average_bits);
+	input->SetOversampleBits(config->oversample_bits);
+	m_analogInputs.emplace(channel, std::move(input));
+}
+
+void IoContainer::HandleCreateDutyCycleEncoder(uint8_t channel, const io_ffi::config::DutyCycleEncoderConfig *config)
+{
+	EnsureDioVacant(channel);
+
+	// report in rotations, with the configured offset as zero
+	std::unique_ptr<frc::DutyCycleEncoder> encoder = std::make_unique<frc::DutyCycleEncoder>(channel, 1.0, config->offset);
+	encoder->SetInverted(config->inverted);
+	m_dutyCycleEncoders.emplace(channel, std::move(encoder));
+}
+
+void IoContainer::EnsureDioVacant(uint8_t channel)
+{
+	// inputs, outputs and encoders share the same dio channels
+	if (m_digitalInputs.contains(channel) || m_digitalOutputs.contains(channel) || m_dutyCycleEncoders.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("DIO channel {} is already in use", channel).c_str()),
+		};
+	}
+}
+
+void IoContainer::HandleDestroy(const ffi::device::Device &device)
+{
+	size_t erased = 0;
+
+	switch (device.kind)
+	{
+	case ffi::device::Type::DigitalInput:
+		erased = m_digitalInputs.erase(device.id);
+		break;
+	case ffi::device::Type::DigitalOutput:
+		erased = m_digitalOutputs.erase(device.id);
+		break;
+	case ffi::device::Type::AnalogInput:
+		erased = m_analogInputs.erase(device.id);
+		break;
+	case ffi::device::Type::DutyCycleEncoder:
+		erased = m_dutyCycleEncoders.erase(device.id);
+		break;
+	default:
+		break;
+	}
+
+	if (erased == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("I/O device (channel {}) does not exist", device.id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,141 @@
+#include "LedContainer.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> LedContainer::HandleCommand(uint8_t channel, const led_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case led_ffi::CommandType::Create:
+		{
+			HandleCreate(channel, *(const size_t *)command->data);
+			break;
+		}
+		case led_ffi::CommandType::SetData:
+		{
+			HandleSetData(channel, (const led_ffi::Pixels *)command->data);
+			break;
+		}
+		case led_ffi::CommandType::SetLength:
+		{
+			HandleSetLength(channel, *(const size_t *)command->data);
+			break;
+		}
+		case led_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(channel);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void LedContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[channel, strip] : m_strips)
+	{
+		led_ffi::Data *data = (led_ffi::Data *)malloc(sizeof(led_ffi::Data));
+		*data = led_ffi::Data{.length = strip.buffer.size()};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::AddressableLed,
+				.bus = ffi::device::Bus::Pwm,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+}
+
+void LedContainer::HandleCreate(uint8_t channel, size_t length)
+{
+	// the roborio can only drive one strip
+	if (!m_strips.empty())
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("LED strip (channel {}) already exists", m_strips.begin()->first).c_str()),
+		};
+	}
+
+	Strip strip{
+		.led = std::make_unique<frc::AddressableLED>(channel),
+		.buffer = std::vector<frc::AddressableLED::LEDData>(length),
+	};
+
+	strip.led->SetLength(length);
+	strip.led->SetData(strip.buffer);
+	strip.led->Start();
+
+	m_strips.emplace(channel, std::move(strip));
+}
+
+void LedContainer::HandleSetData(uint8_t channel, const led_ffi::Pixels *pixels)
+{
+	Strip &strip = Get(channel);
+
+	if (pixels->len != strip.buffer.size())
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadCommand,
+			.message = strdup(fmt::format("LED strip (channel {}) has {} pixels, got {}", channel, strip.buffer.size(), pixels->len).c_str()),
+		};
+	}
+
+	for (size_t i = 0; i < pixels->len; i++)
+	{
+		strip.buffer[i].SetRGB(pixels->data[i].r, pixels->data[i].g, pixels->data[i].b);
+	}
+
+	strip.led->SetData(strip.buffer);
+}
+
+void LedContainer::HandleSetLength(uint8_t channel, size_t length)
+{
+	Strip &strip = Get(channel);
+
+	strip.buffer.resize(length);
+	strip.led->SetLength(length);
+	strip.led->SetData(strip.buffer);
+}
+
+LedContainer::Strip &LedContainer::Get(uint8_t channel)
+{
+	if (!m_strips.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("LED strip (channel {}) does not exist", channel).c_str()),
+		};
+	}
+
+	return m_strips.at(channel);
+}
+
+void LedContainer::HandleDestroy(uint8_t channel)
+{
+	if (m_strips.erase(channel) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("LED strip (channel {}) does not exist", channel).c_str()),
+		};
+	}
+}
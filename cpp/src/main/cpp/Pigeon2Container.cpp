@@ -0,0 +1,141 @@
+#include "Pigeon2Container.h"
+#include "Errors.h"
+#include "Timestamps.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> Pigeon2Container::HandleCommand(uint8_t can_id, const pigeon_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case pigeon_ffi::CommandType::Create:
+		{
+			HandleCreate(can_id, (const pigeon_ffi::config::Pigeon2Config *)command->data);
+			break;
+		}
+		case pigeon_ffi::CommandType::SetYaw:
+		{
+			HandleSetYaw(can_id, *(const double *)command->data);
+			break;
+		}
+		case pigeon_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(can_id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void Pigeon2Container::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[can_id, imu] : m_imus)
+	{
+		auto &yaw = imu->GetYaw();
+		pigeon_ffi::Data *data = (pigeon_ffi::Data *)malloc(sizeof(pigeon_ffi::Data));
+
+		*data = pigeon_ffi::Data{
+			.connected = imu->IsConnected(),
+			.yaw = yaw.GetValueAsDouble(),
+			.pitch = imu->GetPitch().GetValueAsDouble(),
+			.roll = imu->GetRoll().GetValueAsDouble(),
+			.angular_velocity_x = imu->GetAngularVelocityXWorld().GetValueAsDouble(),
+			.angular_velocity_y = imu->GetAngularVelocityYWorld().GetValueAsDouble(),
+			.angular_velocity_z = imu->GetAngularVelocityZWorld().GetValueAsDouble(),
+			.acceleration_x = imu->GetAccelerationX().GetValueAsDouble(),
+			.acceleration_y = imu->GetAccelerationY().GetValueAsDouble(),
+			.acceleration_z = imu->GetAccelerationZ().GetValueAsDouble(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::Pigeon2,
+				.bus = ffi::device::Bus::Can,
+				.id = can_id,
+			},
+			.data = data,
+			.timestamp = timestamps::FromSignal(yaw, now),
+		});
+	}
+}
+
+void Pigeon2Container::HandleCreate(uint8_t can_id, const pigeon_ffi::config::Pigeon2Config *config)
+{
+	if (m_imus.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Pigeon2 (id {}) already exists", can_id).c_str()),
+		};
+	}
+
+	std::unique_ptr<hardware::Pigeon2> imu = std::make_unique<hardware::Pigeon2>(can_id);
+	ctre::phoenix::StatusCode status = imu->GetConfigurator().Apply(Convert(config));
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadConfig, fmt::format("Failed to configure Pigeon2 (id {})", can_id));
+	}
+
+	m_imus.emplace(can_id, std::move(imu));
+}
+
+void Pigeon2Container::HandleSetYaw(uint8_t can_id, double yaw)
+{
+	ctre::phoenix::StatusCode status = Get(can_id)->SetYaw(units::degree_t{yaw});
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadCommand, fmt::format("Pigeon2 (id {}) rejected yaw", can_id));
+	}
+}
+
+hardware::Pigeon2 *Pigeon2Container::Get(uint8_t can_id)
+{
+	if (!m_imus.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Pigeon2 (id {}) does not exist", can_id).c_str()),
+		};
+	}
+
+	return m_imus.at(can_id).get();
+}
+
+configs::Pigeon2Configuration Pigeon2Container::Convert(const pigeon_ffi::config::Pigeon2Config *config)
+{
+	configs::Pigeon2Configuration converted{};
+
+	converted.MountPose.MountPoseYaw = units::degree_t{config->mount_yaw};
+	converted.MountPose.MountPosePitch = units::degree_t{config->mount_pitch};
+	converted.MountPose.MountPoseRoll = units::degree_t{config->mount_roll};
+
+	return converted;
+}
+
+void Pigeon2Container::HandleDestroy(uint8_t can_id)
+{
+	if (m_imus.erase(can_id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Pigeon2 (id {}) does not exist", can_id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,345 @@
+#include "PneumaticsContainer.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> PneumaticsContainer::HandleCommand(const ffi::device::Device &device, const pneumatics_ffi::Command *command)
+{
+	Key key = {device.id, device.channel};
+
+	try
+	{
+		switch (command->kind)
+		{
+		case pneumatics_ffi::CommandType::CreateSolenoid:
+		{
+			HandleCreateSolenoid(key, *(const pneumatics_ffi::config::ModuleType *)command->data);
+			break;
+		}
+		case pneumatics_ffi::CommandType::CreateDoubleSolenoid:
+		{
+			HandleCreateDoubleSolenoid(key, (const pneumatics_ffi::CreateDoubleSolenoid *)command->data);
+			break;
+		}
+		case pneumatics_ffi::CommandType::CreateCompressor:
+		{
+			HandleCreateCompressor(device.id, (const pneumatics_ffi::CreateCompressor *)command->data);
+			break;
+		}
+		case pneumatics_ffi::CommandType::Set:
+		{
+			GetSolenoid(key)->Set(*(const bool *)command->data);
+			break;
+		}
+		case pneumatics_ffi::CommandType::SetDouble:
+		{
+			GetDoubleSolenoid(key)->Set(Convert(*(const pneumatics_ffi::DoubleSolenoidValue *)command->data));
+			break;
+		}
+		case pneumatics_ffi::CommandType::Toggle:
+		{
+			HandleToggle(device, key);
+			break;
+		}
+		case pneumatics_ffi::CommandType::Pulse:
+		{
+			frc::Solenoid *solenoid = GetSolenoid(key);
+			solenoid->SetPulseDuration(units::second_t{*(const double *)command->data});
+			solenoid->StartPulse();
+			break;
+		}
+		case pneumatics_ffi::CommandType::Configure:
+		{
+			Configure(GetCompressor(device.id), *(const pneumatics_ffi::config::CompressorConfig *)command->data);
+			break;
+		}
+		case pneumatics_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(device, key);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void PneumaticsContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	// wpilib doesn't say when a hub's frames were received, so these are never stale
+	for (auto &[key, solenoid] : m_solenoids)
+	{
+		pneumatics_ffi::SolenoidData *data = (pneumatics_ffi::SolenoidData *)malloc(sizeof(pneumatics_ffi::SolenoidData));
+
+		*data = pneumatics_ffi::SolenoidData{
+			.value = solenoid->Get(),
+			.disabled = solenoid->IsDisabled(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::Solenoid,
+				.bus = ffi::device::Bus::Can,
+				.id = key.first,
+				.channel = key.second,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[key, solenoid] : m_doubleSolenoids)
+	{
+		pneumatics_ffi::DoubleSolenoidData *data = (pneumatics_ffi::DoubleSolenoidData *)malloc(sizeof(pneumatics_ffi::DoubleSolenoidData));
+
+		*data = pneumatics_ffi::DoubleSolenoidData{
+			.value = Convert(solenoid->Get()),
+			.disabled = solenoid->IsFwdSolenoidDisabled() || solenoid->IsRevSolenoidDisabled(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::DoubleSolenoid,
+				.bus = ffi::device::Bus::Can,
+				.id = key.first,
+				.channel = key.second,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[module_id, compressor] : m_compressors)
+	{
+		pneumatics_ffi::CompressorData *data = (pneumatics_ffi::CompressorData *)malloc(sizeof(pneumatics_ffi::CompressorData));
+
+		*data = pneumatics_ffi::CompressorData{
+			.enabled = compressor->IsEnabled(),
+			.pressure_switch = compressor->GetPressureSwitchValue(),
+			.pressure = compressor->GetPressure().value(),
+			.current = compressor->GetCurrent().value(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::Compressor,
+				.bus = ffi::device::Bus::Can,
+				.id = module_id,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+}
+
+void PneumaticsContainer::HandleCreateSolenoid(Key key, pneumatics_ffi::config::ModuleType module_type)
+{
+	EnsureVacant(key);
+
+	m_solenoids.emplace(key, std::make_unique<frc::Solenoid>(key.first, Convert(module_type), key.second));
+}
+
+void PneumaticsContainer::HandleCreateDoubleSolenoid(Key key, const pneumatics_ffi::CreateDoubleSolenoid *create)
+{
+	EnsureVacant(key);
+	EnsureVacant({key.first, create->reverse_channel});
+
+	m_doubleSolenoids.emplace(key, std::make_unique<frc::DoubleSolenoid>(key.first, Convert(create->module_type), key.second, create->reverse_channel));
+}
+
+void PneumaticsContainer::HandleCreateCompressor(uint8_t module_id, const pneumatics_ffi::CreateCompressor *create)
+{
+	if (m_compressors.contains(module_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Compressor (module {}) already exists", module_id).c_str()),
+		};
+	}
+
+	std::unique_ptr<frc::Compressor> compressor = std::make_unique<frc::Compressor>(module_id, Convert(create->module_type));
+	Configure(compressor.get(), create->config);
+	m_compressors.emplace(module_id, std::move(compressor));
+}
+
+void PneumaticsContainer::HandleToggle(const ffi::device::Device &device, Key key)
+{
+	if (device.kind == ffi::device::Type::DoubleSolenoid)
+	{
+		GetDoubleSolenoid(key)->Toggle();
+	}
+	else
+	{
+		GetSolenoid(key)->Toggle();
+	}
+}
+
+void PneumaticsContainer::EnsureVacant(Key key)
+{
+	bool used = m_solenoids.contains(key);
+
+	// a double solenoid uses its reverse channel too
+	for (auto &[other, solenoid] : m_doubleSolenoids)
+	{
+		used |= other == key || (other.first == key.first && solenoid->GetRevChannel() == key.second);
+	}
+
+	if (used)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Pneumatics channel {} on module {} is already in use", key.second, key.first).c_str()),
+		};
+	}
+}
+
+frc::Solenoid *PneumaticsContainer::GetSolenoid(Key key)
+{
+	if (!m_solenoids.contains(key))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Solenoid (module {}, channel {}) does not exist", key.first, key.second).c_str()),
+		};
+	}
+
+	return m_solenoids.at(key).get();
+}
+
+frc::DoubleSolenoid *PneumaticsContainer::GetDoubleSolenoid(Key key)
+{
+	if (!m_doubleSolenoids.contains(key))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Double solenoid (module {}, channel {}) does not exist", key.first, key.second).c_str()),
+		};
+	}
+
+	return m_doubleSolenoids.at(key).get();
+}
+
+frc::Compressor *PneumaticsContainer::GetCompressor(uint8_t module_id)
+{
+	if (!m_compressors.contains(module_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Compressor (module {}) does not exist", module_id).c_str()),
+		};
+	}
+
+	return m_compressors.at(module_id).get();
+}
+
+frc::PneumaticsModuleType PneumaticsContainer::Convert(pneumatics_ffi::config::ModuleType module_type)
+{
+	switch (module_type)
+	{
+	case pneumatics_ffi::config::ModuleType::CtrePcm:
+		return frc::PneumaticsModuleType::CTREPCM;
+	case pneumatics_ffi::config::ModuleType::RevPh:
+		return frc::PneumaticsModuleType::REVPH;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown pneumatics module type: {}", (int)module_type).c_str()),
+		};
+	}
+}
+
+frc::DoubleSolenoid::Value PneumaticsContainer::Convert(pneumatics_ffi::DoubleSolenoidValue value)
+{
+	switch (value)
+	{
+	case pneumatics_ffi::DoubleSolenoidValue::Off:
+		return frc::DoubleSolenoid::Value::kOff;
+	case pneumatics_ffi::DoubleSolenoidValue::Forward:
+		return frc::DoubleSolenoid::Value::kForward;
+	case pneumatics_ffi::DoubleSolenoidValue::Reverse:
+		return frc::DoubleSolenoid::Value::kReverse;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadCommand,
+			.message = strdup(fmt::format("Unknown double solenoid value: {}", (int)value).c_str()),
+		};
+	}
+}
+
+pneumatics_ffi::DoubleSolenoidValue PneumaticsContainer::Convert(frc::DoubleSolenoid::Value value)
+{
+	switch (value)
+	{
+	case frc::DoubleSolenoid::Value::kForward:
+		return pneumatics_ffi::DoubleSolenoidValue::Forward;
+	case frc::DoubleSolenoid::Value::kReverse:
+		return pneumatics_ffi::DoubleSolenoidValue::Reverse;
+	default:
+		return pneumatics_ffi::DoubleSolenoidValue::Off;
+	}
+}
+
+void PneumaticsContainer::Configure(frc::Compressor *compressor, const pneumatics_ffi::config::CompressorConfig &config)
+{
+	units::pounds_per_square_inch_t min_pressure{config.min_pressure};
+	units::pounds_per_square_inch_t max_pressure{config.max_pressure};
+
+	switch (config.mode)
+	{
+	case pneumatics_ffi::config::CompressorMode::Disabled:
+		compressor->Disable();
+		break;
+	case pneumatics_ffi::config::CompressorMode::Digital:
+		compressor->EnableDigital();
+		break;
+	case pneumatics_ffi::config::CompressorMode::Analog:
+		compressor->EnableAnalog(min_pressure, max_pressure);
+		break;
+	case pneumatics_ffi::config::CompressorMode::Hybrid:
+		compressor->EnableHybrid(min_pressure, max_pressure);
+		break;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown compressor mode: {}", (int)config.mode).c_str()),
+		};
+	}
+}
+
+void PneumaticsContainer::HandleDestroy(const ffi::device::Device &device, Key key)
+{
+	size_t erased = 0;
+
+	switch (device.kind)
+	{
+	case ffi::device::Type::Solenoid:
+		erased = m_solenoids.erase(key);
+		break;
+	case ffi::device::Type::DoubleSolenoid:
+		erased = m_doubleSolenoids.erase(key);
+		break;
+	case ffi::device::Type::Compressor:
+		erased = m_compressors.erase(device.id);
+		break;
+	default:
+		break;
+	}
+
+	if (erased == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Pneumatics device (module {}, channel {}) does not exist", device.id, device.channel).c_str()),
+		};
+	}
+}
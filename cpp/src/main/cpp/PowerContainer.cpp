@@ -0,0 +1,154 @@
+#include "PowerContainer.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> PowerContainer::HandleCommand(uint8_t can_id, const power_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case power_ffi::CommandType::Create:
+		{
+			HandleCreate(can_id, *(const power_ffi::ModuleType *)command->data);
+			break;
+		}
+		case power_ffi::CommandType::SetSwitchableChannel:
+		{
+			Get(can_id)->SetSwitchableChannel(*(const bool *)command->data);
+			break;
+		}
+		case power_ffi::CommandType::ClearStickyFaults:
+		{
+			Get(can_id)->ClearStickyFaults();
+			break;
+		}
+		case power_ffi::CommandType::ResetTotalEnergy:
+		{
+			Get(can_id)->ResetTotalEnergy();
+			break;
+		}
+		case power_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(can_id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void PowerContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	// wpilib doesn't say when a module's frames were received, so modules are never stale
+	for (auto &[can_id, module] : m_modules)
+	{
+		int channels = module->GetNumChannels();
+		frc::PowerDistribution::Faults faults = module->GetFaults();
+
+		// rust frees this along with the data
+		double *currents = (double *)malloc(sizeof(double) * channels);
+		uint32_t breaker_faults = 0;
+
+		for (int channel = 0; channel < channels; channel++)
+		{
+			currents[channel] = module->GetCurrent(channel);
+
+			if (faults.GetBreakerFault(channel))
+			{
+				breaker_faults |= 1u << channel;
+			}
+		}
+
+		power_ffi::Data *data = (power_ffi::Data *)malloc(sizeof(power_ffi::Data));
+
+		*data = power_ffi::Data{
+			.voltage = module->GetVoltage(),
+			.temperature = module->GetTemperature(),
+			.total_current = module->GetTotalCurrent(),
+			.total_power = module->GetTotalPower(),
+			.total_energy = module->GetTotalEnergy(),
+			.currents = currents,
+			.channels = (size_t)channels,
+			.breaker_faults = breaker_faults,
+			.brownout = (bool)faults.Brownout,
+			.can_warning = (bool)faults.CanWarning,
+			.hardware_fault = (bool)faults.HardwareFault,
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::PowerDistribution,
+				.bus = ffi::device::Bus::Can,
+				.id = can_id,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+}
+
+void PowerContainer::HandleCreate(uint8_t can_id, power_ffi::ModuleType module_type)
+{
+	if (m_modules.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Power distribution (id {}) already exists", can_id).c_str()),
+		};
+	}
+
+	m_modules.emplace(can_id, std::make_unique<frc::PowerDistribution>(can_id, Convert(module_type)));
+}
+
+frc::PowerDistribution *PowerContainer::Get(uint8_t can_id)
+{
+	if (!m_modules.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Power distribution (id {}) does not exist", can_id).c_str()),
+		};
+	}
+
+	return m_modules.at(can_id).get();
+}
+
+frc::PowerDistribution::ModuleType PowerContainer::Convert(power_ffi::ModuleType module_type)
+{
+	switch (module_type)
+	{
+	case power_ffi::ModuleType::Ctre:
+		return frc::PowerDistribution::ModuleType::kCTRE;
+	case power_ffi::ModuleType::Rev:
+		return frc::PowerDistribution::ModuleType::kRev;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown power distribution type: {}", (int)module_type).c_str()),
+		};
+	}
+}
+
+void PowerContainer::HandleDestroy(uint8_t can_id)
+{
+	if (m_modules.erase(can_id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Power distribution module (id {}) does not exist", can_id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,206 @@
+#include "PwmContainer.h"
+#include <fmt/format.h>
+#include <frc/motorcontrol/PWMSparkFlex.h>
+#include <frc/motorcontrol/PWMSparkMax.h>
+#include <frc/motorcontrol/PWMTalonFX.h>
+#include <frc/motorcontrol/PWMTalonSRX.h>
+#include <frc/motorcontrol/PWMVenom.h>
+#include <frc/motorcontrol/PWMVictorSPX.h>
+#include <frc/motorcontrol/Spark.h>
+#include <frc/motorcontrol/Talon.h>
+#include <frc/motorcontrol/VictorSP.h>
+
+std::optional<ffi::device::Error> PwmContainer::HandleCommand(const ffi::device::Device &device, const pwm_ffi::Command *command)
+{
+	uint8_t channel = device.id;
+
+	try
+	{
+		switch (command->kind)
+		{
+		case pwm_ffi::CommandType::CreateMotor:
+		{
+			HandleCreateMotor(channel, (const pwm_ffi::config::PwmMotorConfig *)command->data);
+			break;
+		}
+		case pwm_ffi::CommandType::CreateServo:
+		{
+			HandleCreateServo(channel);
+			break;
+		}
+		case pwm_ffi::CommandType::SetSpeed:
+		{
+			GetMotor(channel)->Set(*(const double *)command->data);
+			break;
+		}
+		case pwm_ffi::CommandType::SetAngle:
+		{
+			GetServo(channel)->SetAngle(*(const double *)command->data);
+			break;
+		}
+		case pwm_ffi::CommandType::SetPosition:
+		{
+			GetServo(channel)->Set(*(const double *)command->data);
+			break;
+		}
+		case pwm_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(device);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void PwmContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[channel, motor] : m_motors)
+	{
+		pwm_ffi::MotorData *data = (pwm_ffi::MotorData *)malloc(sizeof(pwm_ffi::MotorData));
+
+		*data = pwm_ffi::MotorData{
+			.speed = motor->Get(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::PwmMotor,
+				.bus = ffi::device::Bus::Pwm,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+
+	for (auto &[channel, servo] : m_servos)
+	{
+		pwm_ffi::ServoData *data = (pwm_ffi::ServoData *)malloc(sizeof(pwm_ffi::ServoData));
+
+		*data = pwm_ffi::ServoData{
+			.angle = servo->GetAngle(),
+			.position = servo->Get(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::Servo,
+				.bus = ffi::device::Bus::Pwm,
+				.id = channel,
+			},
+			.data = data,
+			.timestamp = now,
+		});
+	}
+}
+
+void PwmContainer::HandleCreateMotor(uint8_t channel, const pwm_ffi::config::PwmMotorConfig *config)
+{
+	EnsureVacant(channel);
+
+	std::unique_ptr<frc::PWMMotorController> motor = Convert(channel, config->controller);
+	motor->SetInverted(config->inverted);
+	m_motors.emplace(channel, std::move(motor));
+}
+
+void PwmContainer::HandleCreateServo(uint8_t channel)
+{
+	EnsureVacant(channel);
+
+	m_servos.emplace(channel, std::make_unique<frc::Servo>(channel));
+}
+
+void PwmContainer::EnsureVacant(uint8_t channel)
+{
+	// motors and servos share the same pwm channels
+	if (m_motors.contains(channel) || m_servos.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("PWM channel {} is already in use", channel).c_str()),
+		};
+	}
+}
+
+frc::PWMMotorController *PwmContainer::GetMotor(uint8_t channel)
+{
+	if (!m_motors.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("PWM motor (channel {}) does not exist", channel).c_str()),
+		};
+	}
+
+	return m_motors.at(channel).get();
+}
+
+frc::Servo *PwmContainer::GetServo(uint8_t channel)
+{
+	if (!m_servos.contains(channel))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Servo (channel {}) does not exist", channel).c_str()),
+		};
+	}
+
+	return m_servos.at(channel).get();
+}
+
+std::unique_ptr<frc::PWMMotorController> PwmContainer::Convert(uint8_t channel, pwm_ffi::config::Controller controller)
+{
+	switch (controller)
+	{
+	case pwm_ffi::config::Controller::Spark:
+		return std::make_unique<frc::Spark>(channel);
+	case pwm_ffi::config::Controller::PwmSparkMax:
+		return std::make_unique<frc::PWMSparkMax>(channel);
+	case pwm_ffi::config::Controller::PwmSparkFlex:
+		return std::make_unique<frc::PWMSparkFlex>(channel);
+	case pwm_ffi::config::Controller::VictorSP:
+		return std::make_unique<frc::VictorSP>(channel);
+	case pwm_ffi::config::Controller::PwmVictorSPX:
+		return std::make_unique<frc::PWMVictorSPX>(channel);
+	case pwm_ffi::config::Controller::Talon:
+		return std::make_unique<frc::Talon>(channel);
+	case pwm_ffi::config::Controller::PwmTalonSRX:
+		return std::make_unique<frc::PWMTalonSRX>(channel);
+	case pwm_ffi::config::Controller::PwmTalonFX:
+		return std::make_unique<frc::PWMTalonFX>(channel);
+	case pwm_ffi::config::Controller::PwmVenom:
+		return std::make_unique<frc::PWMVenom>(channel);
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown PWM controller: {}", (int)controller).c_str()),
+		};
+	}
+}
+
+void PwmContainer::HandleDestroy(const ffi::device::Device &device)
+{
+	size_t erased = device.kind == ffi::device::Type::Servo ? m_servos.erase(device.id) : m_motors.erase(device.id);
+
+	if (erased == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("PWM device (channel {}) does not exist", device.id).c_str()),
+		};
+	}
+}
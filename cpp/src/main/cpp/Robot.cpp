@@ -7,12 +7,17 @@
 #include "rev/config/AbsoluteEncoderConfig.h"
 #include "iostream"
 #include "ffi/ferrobot.h"
+#include "ffi/abi.h"
 
 namespace device = ffi::device;
 
 Robot::Robot()
 {
-	ffi::start_thread();
+	// aborts if the generated bindings are out of date with the library
+	ffi::start_thread(ffi::AbiVersion{
+		.version = ffi::ABI_VERSION,
+		.inventory_hash = ffi::INVENTORY_HASH,
+	});
 }
 
 /**
@@ -23,7 +28,17 @@ Robot::Robot()
  * <p> This runs after the mode specific periodic functions, but before
  * LiveWindow and SmartDashboard integrated updating.
  */
-void Robot::RobotPeriodic() {}
+void Robot::RobotPeriodic()
+{
+	ffi::FFIData data;
+
+	{
+		std::lock_guard lock(m_containerMutex);
+		data = m_robotContainer.CollectData();
+	}
+
+	ffi::supply(data);
+}
 
 /**
  * This function is called once each time the robot enters Disabled mode. You
@@ -66,9 +81,14 @@ void Robot::SimulationPeriodic() {}
 
 extern "C"
 {
-	ffi::Response handle_command(device::Command *command)
+	void handle_commands(const device::Command *commands, size_t len, ffi::Response *responses)
 	{
-		return Robot::m_robotContainer.HandleCommand(command);
+		for (size_t i = 0; i < len; i++)
+		{
+			// locked per command, so data collection waits for at most one
+			std::lock_guard lock(Robot::m_containerMutex);
+			responses[i] = Robot::m_robotContainer.HandleCommand((device::Command *)&commands[i]);
+		}
 	}
 }
 
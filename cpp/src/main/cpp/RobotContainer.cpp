@@ -1,34 +1,137 @@
 #include "RobotContainer.h"
+#include <algorithm>
 #include <iostream>
+#include <fmt/format.h>
+#include <frc/RobotController.h>
+#include <vector>
 
 RobotContainer::RobotContainer() {}
 
 ffi::Response RobotContainer::HandleCommand(device::Command *command)
 {
-	bool ok = true;
-	void *response_ptr = nullptr;
+	std::optional<device::Error> error = std::nullopt;
 
 	switch (command->device.kind)
 	{
 	case device::Type::SparkMax:
+	case device::Type::SparkFlex:
 	{
-		std::optional<spark_ffi::Error> error = m_sparkMaxContainer.HandleCommand(command->device.id, (const spark_ffi::Command *)command->command);
-		if (error.has_value())
-		{
-			ok = false;
-			response_ptr = malloc(sizeof(spark_ffi::Error));
-			*(spark_ffi::Error *)response_ptr = error.value();
-		}
+		error = m_sparkContainer.HandleCommand(command->device.id, (const spark_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::TalonFX:
+	{
+		error = m_talonFXContainer.HandleCommand(command->device.id, (const talonfx_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::CanCoder:
+	{
+		error = m_canCoderContainer.HandleCommand(command->device.id, (const cancoder_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::Pigeon2:
+	{
+		error = m_pigeon2Container.HandleCommand(command->device.id, (const pigeon_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::PwmMotor:
+	case device::Type::Servo:
+	{
+		error = m_pwmContainer.HandleCommand(command->device, (const pwm_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::DigitalInput:
+	case device::Type::DigitalOutput:
+	case device::Type::AnalogInput:
+	case device::Type::DutyCycleEncoder:
+	{
+		error = m_ioContainer.HandleCommand(command->device, (const io_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::Solenoid:
+	case device::Type::DoubleSolenoid:
+	case device::Type::Compressor:
+	{
+		error = m_pneumaticsContainer.HandleCommand(command->device, (const pneumatics_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::PowerDistribution:
+	{
+		error = m_powerContainer.HandleCommand(command->device.id, (const power_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::AddressableLed:
+	{
+		error = m_ledContainer.HandleCommand(command->device.id, (const led_ffi::Command *)command->command);
+		break;
+	}
+	case device::Type::Camera:
+	{
+		error = m_visionContainer.HandleCommand(command->device.id, (const vision_ffi::Command *)command->command);
 		break;
 	}
 	default:
 	{
-		ok = false;
+		error = device::Error{
+			.kind = device::ErrorType::UnsupportedCommand,
+			.message = strdup(fmt::format("Unsupported device type: {}", (int)command->device.kind).c_str()),
+		};
+	}
 	}
+
+	if (!error.has_value())
+	{
+		return ffi::Response{
+			.ok = true,
+			.data = nullptr,
+		};
 	}
 
+	// rust takes ownership of the error and frees it
+	void *response_ptr = malloc(sizeof(device::Error));
+	*(device::Error *)response_ptr = error.value();
+
 	return ffi::Response{
-		.ok = ok,
+		.ok = false,
 		.data = response_ptr,
 	};
+}
+
+ffi::FFIData RobotContainer::CollectData()
+{
+	// each container timestamps its devices with when their data was received,
+	// so rust can tell which have stopped reporting
+	uint64_t now = frc::RobotController::GetFPGATime();
+	std::vector<device::Data> datas = {};
+	m_sparkContainer.CollectData(datas, now);
+	m_talonFXContainer.CollectData(datas, now);
+	m_canCoderContainer.CollectData(datas, now);
+	m_pigeon2Container.CollectData(datas, now);
+	m_pwmContainer.CollectData(datas, now);
+	m_ioContainer.CollectData(datas, now);
+	m_pneumaticsContainer.CollectData(datas, now);
+	m_powerContainer.CollectData(datas, now);
+	m_ledContainer.CollectData(datas, now);
+	m_visionContainer.CollectData(datas, now);
+
+	frc::CANStatus can = frc::RobotController::GetCANStatus();
+
+	// rust takes ownership of this buffer and frees it
+	device::Data *data_ptr = (device::Data *)malloc(sizeof(device::Data) * datas.size());
+	std::copy(datas.begin(), datas.end(), data_ptr);
+
+	return ffi::FFIData{
+		.devices = ffi::DeviceDatas{
+			.data = data_ptr,
+			.len = datas.size(),
+		},
+		.timestamp = now,
+		.can = ffi::CanStatus{
+			.utilization = can.percentBusUtilization,
+			.bus_off_count = (uint32_t)can.busOffCount,
+			.tx_full_count = (uint32_t)can.txFullCount,
+			.rx_error_count = (uint32_t)can.receiveErrorCount,
+			.tx_error_count = (uint32_t)can.transmitErrorCount,
+		},
+	};
 }
\ No newline at end of file
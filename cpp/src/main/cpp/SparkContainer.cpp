@@ -0,0 +1,408 @@
+#include "SparkContainer.h"
+#include "Errors.h"
+#include <iostream>
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> SparkContainer::HandleCommand(uint8_t can_id, const spark_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case spark_ffi::CommandType::Create:
+		{
+			HandleCreate(can_id, (const spark_ffi::config::SparkMaxConfig *)command->data);
+			break;
+		}
+		case spark_ffi::CommandType::CreateFlex:
+		{
+			HandleCreateFlex(can_id, (const spark_ffi::config::SparkFlexConfig *)command->data);
+			break;
+		}
+		case spark_ffi::CommandType::SetEncoderPosition:
+		{
+			HandleSetEncoderPosition(can_id, *(const double *)command->data);
+			break;
+		}
+		case spark_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(can_id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void SparkContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[can_id, entry] : m_motors)
+	{
+		SparkBase *motor = entry.motor.get();
+		bool connected = motor->GetLastError() == rev::REVLibError::kOk;
+		spark_ffi::Data *data = (spark_ffi::Data *)malloc(sizeof(spark_ffi::Data));
+
+		if (connected)
+		{
+			entry.last_received = now;
+		}
+
+		*data = spark_ffi::Data{
+			.connected = connected,
+			.output = motor->GetAppliedOutput(),
+			.position = motor->GetEncoder().GetPosition(),
+			.velocity = motor->GetEncoder().GetVelocity(),
+			.current = motor->GetOutputCurrent(),
+			.has_absolute = entry.has_absolute,
+			.absolute_position = 0.0,
+			.absolute_velocity = 0.0,
+			.has_alternate = entry.has_alternate,
+			.alternate_position = 0.0,
+		};
+
+		if (data->has_absolute)
+		{
+			data->absolute_position = motor->GetAbsoluteEncoder().GetPosition();
+			data->absolute_velocity = motor->GetAbsoluteEncoder().GetVelocity();
+		}
+
+		// the alternate encoder on a spark max is the external encoder on a spark flex
+		if (data->has_alternate && entry.kind == ffi::device::Type::SparkMax)
+		{
+			data->alternate_position = static_cast<SparkMax *>(motor)->GetAlternateEncoder().GetPosition();
+		}
+		else if (data->has_alternate)
+		{
+			data->alternate_position = static_cast<SparkFlex *>(motor)->GetExternalEncoder().GetPosition();
+		}
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = entry.kind,
+				.bus = ffi::device::Bus::Can,
+				.id = can_id,
+			},
+			.data = data,
+			.timestamp = entry.last_received,
+		});
+	}
+}
+
+void SparkContainer::HandleCreate(uint8_t can_id, const spark_ffi::config::SparkMaxConfig *config)
+{
+	EnsureVacant(can_id);
+
+	std::unique_ptr<SparkMaxConfig> converted_config = Convert(config);
+	SparkBase::MotorType motor_type = Convert(config->motor.motor_type);
+	std::unique_ptr<SparkMax> motor = std::make_unique<SparkMax>(can_id, motor_type);
+	rev::REVLibError error = motor->Configure(*converted_config, SparkBase::ResetMode::kResetSafeParameters, SparkBase::PersistMode::kNoPersistParameters);
+
+	if (error != rev::REVLibError::kOk)
+	{
+		throw errors::FromRev(error, ffi::device::ErrorType::BadConfig, fmt::format("Failed to configure SparkMax (id {})", can_id));
+	}
+
+	m_motors.emplace(can_id, Motor{
+		.kind = ffi::device::Type::SparkMax,
+		.motor = std::move(motor),
+		.has_absolute = config->absolute_encoder.enabled,
+		.has_alternate = config->alternate_encoder.enabled,
+	});
+}
+
+void SparkContainer::HandleCreateFlex(uint8_t can_id, const spark_ffi::config::SparkFlexConfig *config)
+{
+	EnsureVacant(can_id);
+
+	std::unique_ptr<SparkFlexConfig> converted_config = Convert(config);
+	SparkBase::MotorType motor_type = Convert(config->motor.motor_type);
+	std::unique_ptr<SparkFlex> motor = std::make_unique<SparkFlex>(can_id, motor_type);
+	rev::REVLibError error = motor->Configure(*converted_config, SparkBase::ResetMode::kResetSafeParameters, SparkBase::PersistMode::kNoPersistParameters);
+
+	if (error != rev::REVLibError::kOk)
+	{
+		throw errors::FromRev(error, ffi::device::ErrorType::BadConfig, fmt::format("Failed to configure SparkFlex (id {})", can_id));
+	}
+
+	m_motors.emplace(can_id, Motor{
+		.kind = ffi::device::Type::SparkFlex,
+		.motor = std::move(motor),
+		.has_absolute = config->absolute_encoder.enabled,
+		.has_alternate = config->external_encoder.enabled,
+	});
+}
+
+void SparkContainer::HandleSetEncoderPosition(uint8_t can_id, double position)
+{
+	if (!m_motors.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Motor (id {}) does not exist", can_id).c_str()),
+		};
+	}
+
+	m_motors.at(can_id).motor->GetEncoder().SetPosition(position);
+}
+
+void SparkContainer::EnsureVacant(uint8_t can_id)
+{
+	if (m_motors.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Motor (id {}) already exists", can_id).c_str()),
+		};
+	}
+}
+
+SparkBase::MotorType SparkContainer::Convert(spark_ffi::config::MotorType motor_type)
+{
+	switch (motor_type)
+	{
+	case spark_ffi::config::MotorType::Brushed:
+		return SparkBase::MotorType::kBrushed;
+	case spark_ffi::config::MotorType::Brushless:
+	case spark_ffi::config::MotorType::Vortex:
+		return SparkBase::MotorType::kBrushless;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown motor type: {}", (int)motor_type).c_str()),
+		};
+	}
+}
+
+ClosedLoopConfig::FeedbackSensor SparkContainer::Convert(spark_ffi::config::FeedbackSensor sensor)
+{
+	switch (sensor)
+	{
+	case spark_ffi::config::FeedbackSensor::None:
+		return ClosedLoopConfig::FeedbackSensor::kNoSensor;
+	case spark_ffi::config::FeedbackSensor::RelativeEncoder:
+		return ClosedLoopConfig::FeedbackSensor::kPrimaryEncoder;
+	case spark_ffi::config::FeedbackSensor::AnalogSensor:
+		return ClosedLoopConfig::FeedbackSensor::kAnalogSensor;
+	case spark_ffi::config::FeedbackSensor::AlternateEncoder:
+		return ClosedLoopConfig::FeedbackSensor::kAlternateOrExternalEncoder;
+	case spark_ffi::config::FeedbackSensor::AbsoluteEncoder:
+		return ClosedLoopConfig::FeedbackSensor::kAbsoluteEncoder;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown feedback sensor: {}", (int)sensor).c_str()),
+		};
+	}
+}
+
+SparkBaseConfig::IdleMode SparkContainer::Convert(spark_ffi::config::IdleMode mode)
+{
+	switch (mode)
+	{
+	case spark_ffi::config::IdleMode::Coast:
+		return SparkBaseConfig::IdleMode::kCoast;
+	case spark_ffi::config::IdleMode::Brake:
+		return SparkBaseConfig::IdleMode::kBrake;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown idle mode: {}", (int)mode).c_str()),
+		};
+	}
+}
+
+template <typename Config>
+void SparkContainer::ConvertBase(SparkBaseConfig *converted, const Config *config)
+{
+	// absolute encoder
+	converted->absoluteEncoder
+		.Inverted(config->absolute_encoder.inverted)
+		.PositionConversionFactor(config->absolute_encoder.position_factor)
+		.VelocityConversionFactor(config->absolute_encoder.velocity_factor)
+		.ZeroOffset(config->absolute_encoder.zero_offset)
+		.AverageDepth(config->absolute_encoder.average_depth)
+		.ZeroCentered(config->absolute_encoder.zero_centered);
+
+	if (config->absolute_encoder.start_pulse_us != -1.0 && config->absolute_encoder.end_pulse_us != -1.0)
+	{
+		converted->absoluteEncoder
+			.StartPulseUs(config->absolute_encoder.start_pulse_us)
+			.EndPulseUs(config->absolute_encoder.end_pulse_us);
+	}
+
+	// closed loop
+	converted->closedLoop
+		.Pidf(
+			config->closed_loop.proportional,
+			config->closed_loop.integral,
+			config->closed_loop.derivative,
+			config->closed_loop.feedforward)
+		.MinOutput(config->closed_loop.min_output)
+		.MaxOutput(config->closed_loop.max_output)
+		.SetFeedbackSensor(Convert(config->closed_loop.feedback_sensor));
+
+	if (config->closed_loop.max_integral != -1.0)
+	{
+		converted->closedLoop.IMaxAccum(config->closed_loop.max_integral);
+	}
+
+	if (config->closed_loop.integral_zone != -1.0)
+	{
+		converted->closedLoop.IZone(config->closed_loop.integral_zone);
+	}
+
+	if (config->closed_loop.position_wrapping)
+	{
+		converted->closedLoop
+			.PositionWrappingEnabled(config->closed_loop.position_wrapping)
+			.PositionWrappingMinInput(config->closed_loop.position_wrap_min)
+			.PositionWrappingMaxInput(config->closed_loop.position_wrap_max);
+	}
+
+	// relative encoder
+	converted->encoder
+		.Inverted(config->relative_encoder.inverted)
+		.PositionConversionFactor(config->relative_encoder.position_factor)
+		.VelocityConversionFactor(config->relative_encoder.velocity_factor);
+
+	if (config->relative_encoder.counts_per_revolution != 0)
+	{
+		converted->encoder.CountsPerRevolution(config->relative_encoder.counts_per_revolution);
+	}
+
+	if (config->relative_encoder.quadrature_average_depth != 0)
+	{
+		converted->encoder.QuadratureAverageDepth(config->relative_encoder.quadrature_average_depth);
+	}
+
+	if (config->relative_encoder.quadrature_measurement_period != 0)
+	{
+		converted->encoder.QuadratureMeasurementPeriod(config->relative_encoder.quadrature_measurement_period);
+	}
+
+	if (config->relative_encoder.uvw_average_depth != 0)
+	{
+		converted->encoder.UvwAverageDepth(config->relative_encoder.uvw_average_depth);
+	}
+
+	if (config->relative_encoder.uvw_measurement_period != 0)
+	{
+		converted->encoder.UvwMeasurementPeriod(config->relative_encoder.uvw_measurement_period);
+	}
+
+	// motor
+	converted->SetIdleMode(Convert(config->motor.idle_mode));
+
+	// inverted means different things based on follow mode or not
+	if (config->motor.leader_id != 0)
+	{
+		converted->Follow(config->motor.leader_id, config->motor.inverted);
+	}
+	else
+	{
+		converted->Inverted(config->motor.inverted);
+	}
+
+	if (config->motor.current_limit != 0.0)
+	{
+		converted->SmartCurrentLimit(config->motor.current_limit);
+	}
+
+	if (config->motor.nominal_voltage != 0.0)
+	{
+		converted->VoltageCompensation(config->motor.nominal_voltage);
+	}
+}
+
+std::unique_ptr<SparkMaxConfig> SparkContainer::Convert(const spark_ffi::config::SparkMaxConfig *config)
+{
+	std::unique_ptr<SparkMaxConfig> converted_uniq = std::make_unique<SparkMaxConfig>();
+	SparkMaxConfig *converted = converted_uniq.get();
+
+	ConvertBase(converted, config);
+
+	// alternate encoder
+	if (config->alternate_encoder.enabled)
+	{
+		converted->alternateEncoder
+			.SetSparkMaxDataPortConfig()
+			.Inverted(config->alternate_encoder.inverted)
+			.PositionConversionFactor(config->alternate_encoder.position_factor)
+			.VelocityConversionFactor(config->alternate_encoder.velocity_factor);
+
+		if (config->alternate_encoder.counts_per_revolution != 0)
+		{
+			converted->alternateEncoder.CountsPerRevolution(config->alternate_encoder.counts_per_revolution);
+		}
+
+		if (config->alternate_encoder.average_depth != 0)
+		{
+			converted->alternateEncoder.AverageDepth(config->alternate_encoder.average_depth);
+		}
+
+		if (config->alternate_encoder.measurement_period != 0)
+		{
+			converted->alternateEncoder.MeasurementPeriod(config->alternate_encoder.measurement_period);
+		}
+	}
+
+	return converted_uniq;
+}
+
+std::unique_ptr<SparkFlexConfig> SparkContainer::Convert(const spark_ffi::config::SparkFlexConfig *config)
+{
+	std::unique_ptr<SparkFlexConfig> converted_uniq = std::make_unique<SparkFlexConfig>();
+	SparkFlexConfig *converted = converted_uniq.get();
+
+	ConvertBase(converted, config);
+
+	// external encoder
+	if (config->external_encoder.enabled)
+	{
+		converted->externalEncoder
+			.Inverted(config->external_encoder.inverted)
+			.PositionConversionFactor(config->external_encoder.position_factor)
+			.VelocityConversionFactor(config->external_encoder.velocity_factor);
+
+		if (config->external_encoder.counts_per_revolution != 0)
+		{
+			converted->externalEncoder.CountsPerRevolution(config->external_encoder.counts_per_revolution);
+		}
+
+		if (config->external_encoder.average_depth != 0)
+		{
+			converted->externalEncoder.AverageDepth(config->external_encoder.average_depth);
+		}
+
+		if (config->external_encoder.measurement_period != 0)
+		{
+			converted->externalEncoder.MeasurementPeriod(config->external_encoder.measurement_period);
+		}
+	}
+
+	return converted_uniq;
+}
+
+void SparkContainer::HandleDestroy(uint8_t can_id)
+{
+	if (m_motors.erase(can_id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Motor (id {}) does not exist", can_id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,228 @@
+#include "TalonFXContainer.h"
+#include "Errors.h"
+#include "Timestamps.h"
+#include <fmt/format.h>
+
+std::optional<ffi::device::Error> TalonFXContainer::HandleCommand(uint8_t can_id, const talonfx_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case talonfx_ffi::CommandType::Create:
+		{
+			HandleCreate(can_id, (const talonfx_ffi::config::TalonFXConfig *)command->data);
+			break;
+		}
+		case talonfx_ffi::CommandType::SetDutyCycle:
+		{
+			HandleControl(can_id, controls::DutyCycleOut{*(const double *)command->data});
+			break;
+		}
+		case talonfx_ffi::CommandType::SetVoltage:
+		{
+			HandleControl(can_id, controls::VoltageOut{units::volt_t{*(const double *)command->data}});
+			break;
+		}
+		case talonfx_ffi::CommandType::SetVelocity:
+		{
+			const talonfx_ffi::Setpoint *setpoint = (const talonfx_ffi::Setpoint *)command->data;
+			HandleControl(can_id, controls::VelocityVoltage{units::turns_per_second_t{setpoint->value}}.WithSlot((int)setpoint->slot));
+			break;
+		}
+		case talonfx_ffi::CommandType::SetPosition:
+		{
+			const talonfx_ffi::Setpoint *setpoint = (const talonfx_ffi::Setpoint *)command->data;
+			HandleControl(can_id, controls::PositionVoltage{units::turn_t{setpoint->value}}.WithSlot((int)setpoint->slot));
+			break;
+		}
+		case talonfx_ffi::CommandType::SetMotionMagic:
+		{
+			const talonfx_ffi::Setpoint *setpoint = (const talonfx_ffi::Setpoint *)command->data;
+			HandleControl(can_id, controls::MotionMagicVoltage{units::turn_t{setpoint->value}}.WithSlot((int)setpoint->slot));
+			break;
+		}
+		case talonfx_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(can_id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void TalonFXContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[can_id, motor] : m_motors)
+	{
+		auto &position = motor->GetPosition();
+		talonfx_ffi::Data *data = (talonfx_ffi::Data *)malloc(sizeof(talonfx_ffi::Data));
+
+		*data = talonfx_ffi::Data{
+			.connected = motor->IsConnected(),
+			.position = position.GetValueAsDouble(),
+			.velocity = motor->GetVelocity().GetValueAsDouble(),
+			.acceleration = motor->GetAcceleration().GetValueAsDouble(),
+			.duty_cycle = motor->GetDutyCycle().GetValueAsDouble(),
+			.motor_voltage = motor->GetMotorVoltage().GetValueAsDouble(),
+			.supply_voltage = motor->GetSupplyVoltage().GetValueAsDouble(),
+			.stator_current = motor->GetStatorCurrent().GetValueAsDouble(),
+			.supply_current = motor->GetSupplyCurrent().GetValueAsDouble(),
+			.temperature = motor->GetDeviceTemp().GetValueAsDouble(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::TalonFX,
+				.bus = ffi::device::Bus::Can,
+				.id = can_id,
+			},
+			.data = data,
+			.timestamp = timestamps::FromSignal(position, now),
+		});
+	}
+}
+
+void TalonFXContainer::HandleCreate(uint8_t can_id, const talonfx_ffi::config::TalonFXConfig *config)
+{
+	if (m_motors.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("TalonFX (id {}) already exists", can_id).c_str()),
+		};
+	}
+
+	std::unique_ptr<hardware::TalonFX> motor = std::make_unique<hardware::TalonFX>(can_id);
+	ctre::phoenix::StatusCode status = motor->GetConfigurator().Apply(Convert(config));
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadConfig, fmt::format("Failed to configure TalonFX (id {})", can_id));
+	}
+
+	m_motors.emplace(can_id, std::move(motor));
+}
+
+void TalonFXContainer::HandleControl(uint8_t can_id, const controls::ControlRequest &request)
+{
+	ctre::phoenix::StatusCode status = Get(can_id)->SetControl(request);
+
+	if (!status.IsOK())
+	{
+		throw errors::FromStatus(status, ffi::device::ErrorType::BadCommand, fmt::format("TalonFX (id {}) rejected {}", can_id, request.GetName()));
+	}
+}
+
+hardware::TalonFX *TalonFXContainer::Get(uint8_t can_id)
+{
+	if (!m_motors.contains(can_id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("TalonFX (id {}) does not exist", can_id).c_str()),
+		};
+	}
+
+	return m_motors.at(can_id).get();
+}
+
+signals::GravityTypeValue TalonFXContainer::Convert(talonfx_ffi::config::GravityType gravity_type)
+{
+	switch (gravity_type)
+	{
+	case talonfx_ffi::config::GravityType::ElevatorStatic:
+		return signals::GravityTypeValue::Elevator_Static;
+	case talonfx_ffi::config::GravityType::ArmCosine:
+		return signals::GravityTypeValue::Arm_Cosine;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown gravity type: {}", (int)gravity_type).c_str()),
+		};
+	}
+}
+
+signals::NeutralModeValue TalonFXContainer::Convert(talonfx_ffi::config::NeutralMode mode)
+{
+	switch (mode)
+	{
+	case talonfx_ffi::config::NeutralMode::Coast:
+		return signals::NeutralModeValue::Coast;
+	case talonfx_ffi::config::NeutralMode::Brake:
+		return signals::NeutralModeValue::Brake;
+	default:
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::BadConfig,
+			.message = strdup(fmt::format("Unknown neutral mode: {}", (int)mode).c_str()),
+		};
+	}
+}
+
+template <typename Slot>
+void TalonFXContainer::ConvertSlot(Slot &converted, const talonfx_ffi::config::SlotConfig &slot)
+{
+	converted.kP = slot.kp;
+	converted.kI = slot.ki;
+	converted.kD = slot.kd;
+	converted.kS = slot.ks;
+	converted.kV = slot.kv;
+	converted.kA = slot.ka;
+	converted.kG = slot.kg;
+	converted.GravityType = Convert(slot.gravity_type);
+}
+
+configs::TalonFXConfiguration TalonFXContainer::Convert(const talonfx_ffi::config::TalonFXConfig *config)
+{
+	configs::TalonFXConfiguration converted{};
+
+	// slots
+	ConvertSlot(converted.Slot0, config->slot0);
+	ConvertSlot(converted.Slot1, config->slot1);
+	ConvertSlot(converted.Slot2, config->slot2);
+
+	// motion magic
+	converted.MotionMagic.MotionMagicCruiseVelocity = units::turns_per_second_t{config->motion_magic.cruise_velocity};
+	converted.MotionMagic.MotionMagicAcceleration = units::turns_per_second_squared_t{config->motion_magic.acceleration};
+	converted.MotionMagic.MotionMagicJerk = units::turns_per_second_cubed_t{config->motion_magic.jerk};
+
+	// current limits
+	converted.CurrentLimits.StatorCurrentLimit = units::ampere_t{config->current_limits.stator_limit};
+	converted.CurrentLimits.StatorCurrentLimitEnable = config->current_limits.stator_limit_enable;
+	converted.CurrentLimits.SupplyCurrentLimit = units::ampere_t{config->current_limits.supply_limit};
+	converted.CurrentLimits.SupplyCurrentLimitEnable = config->current_limits.supply_limit_enable;
+
+	// motor output
+	converted.MotorOutput.NeutralMode = Convert(config->neutral_mode);
+	converted.MotorOutput.Inverted = config->inverted ? signals::InvertedValue::Clockwise_Positive : signals::InvertedValue::CounterClockwise_Positive;
+
+	// feedback
+	converted.Feedback.SensorToMechanismRatio = config->sensor_to_mechanism_ratio;
+
+	return converted;
+}
+
+void TalonFXContainer::HandleDestroy(uint8_t can_id)
+{
+	if (m_motors.erase(can_id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("TalonFX (id {}) does not exist", can_id).c_str()),
+		};
+	}
+}
@@ -0,0 +1,131 @@
+#include "VisionContainer.h"
+#include <algorithm>
+#include <fmt/format.h>
+#include <networktables/NetworkTableInstance.h>
+
+std::optional<ffi::device::Error> VisionContainer::HandleCommand(uint8_t id, const vision_ffi::Command *command)
+{
+	try
+	{
+		switch (command->kind)
+		{
+		case vision_ffi::CommandType::Create:
+		{
+			HandleCreate(id, (const char *)command->data);
+			break;
+		}
+		case vision_ffi::CommandType::Destroy:
+		{
+			HandleDestroy(id);
+			break;
+		}
+		default:
+		{
+			return ffi::device::Error{
+				.kind = ffi::device::ErrorType::UnsupportedCommand,
+				.message = strdup(fmt::format("Unknown command type: {}", (int)command->kind).c_str()),
+			};
+			break;
+		}
+		}
+	}
+	catch (ffi::device::Error err)
+	{
+		return err;
+	}
+
+	return std::nullopt;
+}
+
+void VisionContainer::CollectData(std::vector<ffi::device::Data> &out, uint64_t now)
+{
+	for (auto &[id, camera] : m_cameras)
+	{
+		nt::TimestampedDoubleArray targets = camera.targets.GetAtomic();
+		nt::TimestampedDoubleArray pose = camera.pose.GetAtomic();
+		nt::TimestampedDouble latency = camera.latency.GetAtomic();
+
+		// the coprocessor publishes a frame's values together, with the same
+		// time. a pose or latency with a different time belongs to another
+		// frame, as it was read mid update, so it's left out of this one
+		if (pose.time != targets.time)
+		{
+			pose.value.clear();
+		}
+
+		if (latency.time != targets.time)
+		{
+			latency.value = 0.0;
+		}
+
+		// nt time is in microseconds, on the same timebase as the fpga clock.
+		// the coprocessor reports its pipeline latency in milliseconds
+		double timestamp = targets.time / 1e6 - latency.value / 1e3;
+
+		vision_ffi::Data *data = (vision_ffi::Data *)malloc(sizeof(vision_ffi::Data));
+
+		*data = vision_ffi::Data{
+			.connected = camera.targets.Exists() && targets.time != 0,
+			.timestamp = timestamp,
+			.targets = Copy(targets.value),
+			.targets_len = targets.value.size(),
+			.pose = Copy(pose.value),
+			.pose_len = pose.value.size(),
+		};
+
+		out.push_back(ffi::device::Data{
+			.device = ffi::device::Device{
+				.kind = ffi::device::Type::Camera,
+				.bus = ffi::device::Bus::Network,
+				.id = id,
+			},
+			.data = data,
+			// when the coprocessor last published, so a camera that stops is stale
+			.timestamp = (uint64_t)targets.time,
+		});
+	}
+}
+
+void VisionContainer::HandleCreate(uint8_t id, const char *table_name)
+{
+	if (m_cameras.contains(id))
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceExists,
+			.message = strdup(fmt::format("Camera (id {}) already exists", id).c_str()),
+		};
+	}
+
+	std::shared_ptr<nt::NetworkTable> table = nt::NetworkTableInstance::GetDefault().GetTable(table_name);
+
+	m_cameras.emplace(id, Camera{
+		.targets = table->GetDoubleArrayTopic("targets").Subscribe({}),
+		.pose = table->GetDoubleArrayTopic("pose").Subscribe({}),
+		.latency = table->GetDoubleTopic("latency").Subscribe(0.0),
+	});
+}
+
+double *VisionContainer::Copy(const std::vector<double> &values)
+{
+	if (values.empty())
+	{
+		return nullptr;
+	}
+
+	// rust frees this along with the data
+	double *copy = (double *)malloc(sizeof(double) * values.size());
+	std::copy(values.begin(), values.end(), copy);
+
+	return copy;
+}
+
+void VisionContainer::HandleDestroy(uint8_t id)
+{
+	if (m_cameras.erase(id) == 0)
+	{
+		throw ffi::device::Error{
+			.kind = ffi::device::ErrorType::DeviceNotFound,
+			.message = strdup(fmt::format("Camera (id {}) does not exist", id).c_str()),
+		};
+	}
+}
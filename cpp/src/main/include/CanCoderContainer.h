@@ -0,0 +1,31 @@
+#pragma once
+
+#include <ctre/phoenix6/CANcoder.hpp>
+#include <ffi/device.h>
+#include <ffi/device/cancoder.h>
+#include <ffi/device/cancoder/config.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+using namespace ctre::phoenix6;
+namespace cancoder_ffi = ffi::device::cancoder;
+
+class CanCoderContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t can_id, const cancoder_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleCreate(uint8_t can_id, const cancoder_ffi::config::CanCoderConfig *config);
+	void HandleDestroy(uint8_t can_id);
+	void HandleSetPosition(uint8_t can_id, double position);
+	hardware::CANcoder *Get(uint8_t can_id);
+
+	static configs::CANcoderConfiguration Convert(const cancoder_ffi::config::CanCoderConfig *config);
+	static cancoder_ffi::MagnetHealth Convert(signals::MagnetHealthValue health);
+
+	std::map<uint8_t, std::unique_ptr<hardware::CANcoder>> m_encoders = {};
+};
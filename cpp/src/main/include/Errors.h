@@ -0,0 +1,66 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ctre/phoenix/StatusCodes.h>
+#include <rev/REVLibError.h>
+#include <fmt/format.h>
+#include <cstring>
+#include <string>
+
+namespace errors
+{
+	/// Build an error from a Phoenix status code, keeping the code so it can be inspected from Rust
+	inline ffi::device::Error FromStatus(ctre::phoenix::StatusCode status, ffi::device::ErrorType fallback, const std::string &message)
+	{
+		ffi::device::ErrorType kind = fallback;
+
+		if (status == ctre::phoenix::StatusCode::RxTimeout || status == ctre::phoenix::StatusCode::TxTimeout)
+		{
+			kind = ffi::device::ErrorType::Timeout;
+		}
+		else if (status == ctre::phoenix::StatusCode::EcuIsNotPresent)
+		{
+			kind = ffi::device::ErrorType::CanFault;
+		}
+
+		return ffi::device::Error{
+			.kind = kind,
+			.message = strdup(fmt::format("{}: {}", message, status.GetName()).c_str()),
+			.code = (int32_t)status,
+			.rev_status = 0,
+		};
+	}
+
+	/// Build an error from a REVLib error, keeping the code so it can be inspected from Rust
+	inline ffi::device::Error FromRev(rev::REVLibError error, ffi::device::ErrorType fallback, const std::string &message)
+	{
+		ffi::device::ErrorType kind = fallback;
+
+		switch (error)
+		{
+		case rev::REVLibError::kTimeout:
+			kind = ffi::device::ErrorType::Timeout;
+			break;
+		case rev::REVLibError::kCANDisconnected:
+		case rev::REVLibError::kDuplicateCANId:
+		case rev::REVLibError::kInvalidCANId:
+			kind = ffi::device::ErrorType::CanFault;
+			break;
+		case rev::REVLibError::kHALError:
+			kind = ffi::device::ErrorType::Hal;
+			break;
+		case rev::REVLibError::kNotImplemented:
+			kind = ffi::device::ErrorType::UnsupportedCommand;
+			break;
+		default:
+			break;
+		}
+
+		return ffi::device::Error{
+			.kind = kind,
+			.message = strdup(fmt::format("{}: REVLibError {}", message, (int)error).c_str()),
+			.code = 0,
+			.rev_status = (int32_t)error,
+		};
+	}
+}
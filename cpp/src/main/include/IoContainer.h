@@ -0,0 +1,33 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/io.h>
+#include <ffi/device/io/config.h>
+#include <frc/AnalogInput.h>
+#include <frc/DigitalInput.h>
+#include <frc/DigitalOutput.h>
+#include <frc/DutyCycleEncoder.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+namespace io_ffi = ffi::device::io;
+
+class IoContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(const ffi::device::Device &device, const io_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleDestroy(const ffi::device::Device &device);
+	void HandleCreateAnalogInput(uint8_t channel, const io_ffi::config::AnalogInputConfig *config);
+	void HandleCreateDutyCycleEncoder(uint8_t channel, const io_ffi::config::DutyCycleEncoderConfig *config);
+	void EnsureDioVacant(uint8_t channel);
+
+	std::map<uint8_t, std::unique_ptr<frc::DigitalInput>> m_digitalInputs = {};
+	std::map<uint8_t, std::unique_ptr<frc::DigitalOutput>> m_digitalOutputs = {};
+	std::map<uint8_t, std::unique_ptr<frc::AnalogInput>> m_analogInputs = {};
+	std::map<uint8_t, std::unique_ptr<frc::DutyCycleEncoder>> m_dutyCycleEncoders = {};
+};
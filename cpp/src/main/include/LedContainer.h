@@ -0,0 +1,33 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/led.h>
+#include <frc/AddressableLED.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+namespace led_ffi = ffi::device::led;
+
+class LedContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t channel, const led_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	struct Strip
+	{
+		std::unique_ptr<frc::AddressableLED> led;
+		std::vector<frc::AddressableLED::LEDData> buffer;
+	};
+
+	void HandleCreate(uint8_t channel, size_t length);
+	void HandleDestroy(uint8_t channel);
+	void HandleSetData(uint8_t channel, const led_ffi::Pixels *pixels);
+	void HandleSetLength(uint8_t channel, size_t length);
+	Strip &Get(uint8_t channel);
+
+	std::map<uint8_t, Strip> m_strips = {};
+};
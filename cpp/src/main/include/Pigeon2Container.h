@@ -0,0 +1,30 @@
+#pragma once
+
+#include <ctre/phoenix6/Pigeon2.hpp>
+#include <ffi/device.h>
+#include <ffi/device/pigeon.h>
+#include <ffi/device/pigeon/config.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+using namespace ctre::phoenix6;
+namespace pigeon_ffi = ffi::device::pigeon;
+
+class Pigeon2Container
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t can_id, const pigeon_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleCreate(uint8_t can_id, const pigeon_ffi::config::Pigeon2Config *config);
+	void HandleDestroy(uint8_t can_id);
+	void HandleSetYaw(uint8_t can_id, double yaw);
+	hardware::Pigeon2 *Get(uint8_t can_id);
+
+	static configs::Pigeon2Configuration Convert(const pigeon_ffi::config::Pigeon2Config *config);
+
+	std::map<uint8_t, std::unique_ptr<hardware::Pigeon2>> m_imus = {};
+};
@@ -0,0 +1,46 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/pneumatics.h>
+#include <ffi/device/pneumatics/config.h>
+#include <frc/Compressor.h>
+#include <frc/DoubleSolenoid.h>
+#include <frc/PneumaticsModuleType.h>
+#include <frc/Solenoid.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <utility>
+#include <vector>
+
+namespace pneumatics_ffi = ffi::device::pneumatics;
+
+class PneumaticsContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(const ffi::device::Device &device, const pneumatics_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	// (module id, channel)
+	using Key = std::pair<uint8_t, uint8_t>;
+
+	void HandleCreateSolenoid(Key key, pneumatics_ffi::config::ModuleType module_type);
+	void HandleCreateDoubleSolenoid(Key key, const pneumatics_ffi::CreateDoubleSolenoid *create);
+	void HandleCreateCompressor(uint8_t module_id, const pneumatics_ffi::CreateCompressor *create);
+	void HandleToggle(const ffi::device::Device &device, Key key);
+	void HandleDestroy(const ffi::device::Device &device, Key key);
+	void EnsureVacant(Key key);
+	frc::Solenoid *GetSolenoid(Key key);
+	frc::DoubleSolenoid *GetDoubleSolenoid(Key key);
+	frc::Compressor *GetCompressor(uint8_t module_id);
+
+	static frc::PneumaticsModuleType Convert(pneumatics_ffi::config::ModuleType module_type);
+	static frc::DoubleSolenoid::Value Convert(pneumatics_ffi::DoubleSolenoidValue value);
+	static pneumatics_ffi::DoubleSolenoidValue Convert(frc::DoubleSolenoid::Value value);
+	static void Configure(frc::Compressor *compressor, const pneumatics_ffi::config::CompressorConfig &config);
+
+	std::map<Key, std::unique_ptr<frc::Solenoid>> m_solenoids = {};
+	std::map<Key, std::unique_ptr<frc::DoubleSolenoid>> m_doubleSolenoids = {};
+	std::map<uint8_t, std::unique_ptr<frc::Compressor>> m_compressors = {};
+};
@@ -0,0 +1,27 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/power.h>
+#include <frc/PowerDistribution.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+namespace power_ffi = ffi::device::power;
+
+class PowerContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t can_id, const power_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleCreate(uint8_t can_id, power_ffi::ModuleType module_type);
+	void HandleDestroy(uint8_t can_id);
+	frc::PowerDistribution *Get(uint8_t can_id);
+
+	static frc::PowerDistribution::ModuleType Convert(power_ffi::ModuleType module_type);
+
+	std::map<uint8_t, std::unique_ptr<frc::PowerDistribution>> m_modules = {};
+};
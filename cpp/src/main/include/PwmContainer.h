@@ -0,0 +1,33 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/pwm.h>
+#include <ffi/device/pwm/config.h>
+#include <frc/Servo.h>
+#include <frc/motorcontrol/PWMMotorController.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+namespace pwm_ffi = ffi::device::pwm;
+
+class PwmContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(const ffi::device::Device &device, const pwm_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleDestroy(const ffi::device::Device &device);
+	void HandleCreateMotor(uint8_t channel, const pwm_ffi::config::PwmMotorConfig *config);
+	void HandleCreateServo(uint8_t channel);
+	void EnsureVacant(uint8_t channel);
+	frc::PWMMotorController *GetMotor(uint8_t channel);
+	frc::Servo *GetServo(uint8_t channel);
+
+	static std::unique_ptr<frc::PWMMotorController> Convert(uint8_t channel, pwm_ffi::config::Controller controller);
+
+	std::map<uint8_t, std::unique_ptr<frc::PWMMotorController>> m_motors = {};
+	std::map<uint8_t, std::unique_ptr<frc::Servo>> m_servos = {};
+};
@@ -4,6 +4,7 @@
 
 #pragma once
 
+#include <mutex>
 #include <optional>
 
 #include <frc/TimedRobot.h>
@@ -15,6 +16,9 @@ class Robot : public frc::TimedRobot
 {
 public:
 	inline static RobotContainer m_robotContainer = RobotContainer();
+	// rust handles commands from its own threads, so they can create or
+	// destroy devices while data is collected
+	inline static std::mutex m_containerMutex;
 
 	Robot();
 	void RobotPeriodic() override;
@@ -33,6 +37,6 @@ private:
 
 extern "C"
 {
-	// C function to start the robot
-	ffi::Response handle_command(device::Command *command);
+	// handles the commands rust queued since the last tick, in order
+	void handle_commands(const device::Command *commands, size_t len, ffi::Response *responses);
 }
\ No newline at end of file
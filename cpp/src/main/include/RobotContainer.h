@@ -1,6 +1,15 @@
 #pragma once
 
-#include <SparkMaxContainer.h>
+#include <CanCoderContainer.h>
+#include <IoContainer.h>
+#include <LedContainer.h>
+#include <Pigeon2Container.h>
+#include <PneumaticsContainer.h>
+#include <PowerContainer.h>
+#include <PwmContainer.h>
+#include <SparkContainer.h>
+#include <TalonFXContainer.h>
+#include <VisionContainer.h>
 #include <ffi/device.h>
 #include <ffi/ferrobot.h>
 
@@ -11,7 +20,17 @@ class RobotContainer
 public:
 	RobotContainer();
 	ffi::Response HandleCommand(device::Command *command);
+	ffi::FFIData CollectData();
 
 private:
-	SparkMaxContainer m_sparkMaxContainer = SparkMaxContainer();
+	IoContainer m_ioContainer = IoContainer();
+	LedContainer m_ledContainer = LedContainer();
+	PneumaticsContainer m_pneumaticsContainer = PneumaticsContainer();
+	PowerContainer m_powerContainer = PowerContainer();
+	PwmContainer m_pwmContainer = PwmContainer();
+	SparkContainer m_sparkContainer = SparkContainer();
+	TalonFXContainer m_talonFXContainer = TalonFXContainer();
+	CanCoderContainer m_canCoderContainer = CanCoderContainer();
+	Pigeon2Container m_pigeon2Container = Pigeon2Container();
+	VisionContainer m_visionContainer = VisionContainer();
 };
\ No newline at end of file
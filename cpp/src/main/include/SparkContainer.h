@@ -0,0 +1,51 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/spark.h>
+#include <ffi/device/spark/config.h>
+#include <rev/SparkFlex.h>
+#include <rev/SparkMax.h>
+#include <rev/config/SparkFlexConfig.h>
+#include <rev/config/SparkMaxConfig.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+using namespace rev::spark;
+namespace spark_ffi = ffi::device::spark;
+
+class SparkContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t can_id, const spark_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	struct Motor
+	{
+		ffi::device::Type kind;
+		std::unique_ptr<SparkBase> motor;
+		bool has_absolute;
+		bool has_alternate;
+		// fpga time the motor last responded, as revlib doesn't timestamp its frames
+		uint64_t last_received = 0;
+	};
+
+	void HandleCreate(uint8_t can_id, const spark_ffi::config::SparkMaxConfig *config);
+	void HandleDestroy(uint8_t can_id);
+	void HandleCreateFlex(uint8_t can_id, const spark_ffi::config::SparkFlexConfig *config);
+	void HandleSetEncoderPosition(uint8_t can_id, double position);
+	void EnsureVacant(uint8_t can_id);
+
+	static SparkBase::MotorType Convert(spark_ffi::config::MotorType motor_type);
+	static std::unique_ptr<SparkMaxConfig> Convert(const spark_ffi::config::SparkMaxConfig *config);
+	static std::unique_ptr<SparkFlexConfig> Convert(const spark_ffi::config::SparkFlexConfig *config);
+	static ClosedLoopConfig::FeedbackSensor Convert(spark_ffi::config::FeedbackSensor sensor);
+	static SparkBaseConfig::IdleMode Convert(spark_ffi::config::IdleMode mode);
+
+	template <typename Config>
+	static void ConvertBase(SparkBaseConfig *converted, const Config *config);
+
+	std::map<uint8_t, Motor> m_motors = {};
+};
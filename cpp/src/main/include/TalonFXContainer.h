@@ -0,0 +1,35 @@
+#pragma once
+
+#include <ctre/phoenix6/TalonFX.hpp>
+#include <ffi/device.h>
+#include <ffi/device/talonfx.h>
+#include <ffi/device/talonfx/config.h>
+#include <map>
+#include <memory>
+#include <optional>
+#include <vector>
+
+using namespace ctre::phoenix6;
+namespace talonfx_ffi = ffi::device::talonfx;
+
+class TalonFXContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t can_id, const talonfx_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	void HandleCreate(uint8_t can_id, const talonfx_ffi::config::TalonFXConfig *config);
+	void HandleDestroy(uint8_t can_id);
+	void HandleControl(uint8_t can_id, const controls::ControlRequest &request);
+	hardware::TalonFX *Get(uint8_t can_id);
+
+	static configs::TalonFXConfiguration Convert(const talonfx_ffi::config::TalonFXConfig *config);
+	static signals::GravityTypeValue Convert(talonfx_ffi::config::GravityType gravity_type);
+	static signals::NeutralModeValue Convert(talonfx_ffi::config::NeutralMode mode);
+
+	template <typename Slot>
+	static void ConvertSlot(Slot &converted, const talonfx_ffi::config::SlotConfig &slot);
+
+	std::map<uint8_t, std::unique_ptr<hardware::TalonFX>> m_motors = {};
+};
@@ -0,0 +1,23 @@
+#pragma once
+
+#include <ctre/phoenix6/StatusSignal.hpp>
+#include <units/time.h>
+#include <cstdint>
+
+namespace timestamps
+{
+	/// FPGA time a Phoenix signal was last received, in microseconds, or 0 if it never has been, so rust can tell when
+	/// the device stops reporting. `now` is the current FPGA time
+	inline uint64_t FromSignal(const ctre::phoenix6::BaseStatusSignal &signal, uint64_t now)
+	{
+		const ctre::phoenix6::Timestamp &timestamp = signal.GetTimestamp();
+
+		if (!timestamp.IsValid())
+		{
+			return 0;
+		}
+
+		uint64_t age = (uint64_t)units::microsecond_t{timestamp.GetLatency()}.value();
+		return age < now ? now - age : 0;
+	}
+}
@@ -0,0 +1,33 @@
+#pragma once
+
+#include <ffi/device.h>
+#include <ffi/device/vision.h>
+#include <networktables/DoubleArrayTopic.h>
+#include <networktables/DoubleTopic.h>
+#include <map>
+#include <optional>
+#include <vector>
+
+namespace vision_ffi = ffi::device::vision;
+
+class VisionContainer
+{
+public:
+	std::optional<ffi::device::Error> HandleCommand(uint8_t id, const vision_ffi::Command *command);
+	void CollectData(std::vector<ffi::device::Data> &out, uint64_t now);
+
+private:
+	struct Camera
+	{
+		nt::DoubleArraySubscriber targets;
+		nt::DoubleArraySubscriber pose;
+		nt::DoubleSubscriber latency;
+	};
+
+	void HandleCreate(uint8_t id, const char *table);
+	void HandleDestroy(uint8_t id);
+
+	static double *Copy(const std::vector<double> &values);
+
+	std::map<uint8_t, Camera> m_cameras = {};
+};
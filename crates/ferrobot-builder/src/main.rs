@@ -33,6 +33,12 @@ fn run(args: Arguments) -> TaskResult {
 
             // write bindings
             interop.write_all(&paths::INCLUDE)?;
+
+            // write the abi version the robot checks the library against
+            fs::write(
+                paths::FFI_INCLUDE.join("abi.h"),
+                ferrobot::build::__ffi_abi_header(),
+            )?;
         }
         Operation::Build { mode } => {
             run(Arguments {
@@ -0,0 +1,141 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use quote::ToTokens;
+use syn::{
+    Attribute, Field, Item, Variant,
+    visit_mut::{self, VisitMut},
+};
+
+// FNV-1a, so the hash is stable across toolchains and builds
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Attributes marking an item as part of the interoptopus inventory
+const FFI_ATTRIBUTES: [&str; 3] = ["ffi_type", "ffi_function", "ffi_constant"];
+
+fn hash(state: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(state, |state, &byte| {
+        (state ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("Failed to read source directory") {
+        let path = entry.expect("Failed to read source directory").path();
+
+        if path.is_dir() {
+            sources(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+fn strip_docs(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !attr.path().is_ident("doc"));
+}
+
+/// Removes doc comments from an item's fields and variants
+struct StripDocs;
+
+impl VisitMut for StripDocs {
+    fn visit_field_mut(&mut self, field: &mut Field) {
+        strip_docs(&mut field.attrs);
+        visit_mut::visit_field_mut(self, field);
+    }
+
+    fn visit_variant_mut(&mut self, variant: &mut Variant) {
+        strip_docs(&mut variant.attrs);
+        visit_mut::visit_variant_mut(self, variant);
+    }
+}
+
+fn is_ffi(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| FFI_ATTRIBUTES.iter().any(|name| segment.ident == name))
+    })
+}
+
+/// The tokens of every item in the inventory, without docs or function
+/// bodies, so only changes to the bindings change the hash
+fn ffi_items(items: Vec<Item>, out: &mut Vec<String>) {
+    for item in items {
+        match item {
+            Item::Mod(module) => ffi_items(
+                module.content.map(|(_, items)| items).unwrap_or_default(),
+                out,
+            ),
+            Item::Fn(mut function) if is_ffi(&function.attrs) => {
+                strip_docs(&mut function.attrs);
+
+                let attrs = function
+                    .attrs
+                    .iter()
+                    .map(|attr| attr.to_token_stream().to_string());
+                out.push(
+                    attrs
+                        .chain([function.sig.to_token_stream().to_string()])
+                        .collect(),
+                );
+            }
+            Item::Struct(mut item) if is_ffi(&item.attrs) => {
+                strip_docs(&mut item.attrs);
+                StripDocs.visit_item_struct_mut(&mut item);
+                out.push(item.to_token_stream().to_string());
+            }
+            Item::Enum(mut item) if is_ffi(&item.attrs) => {
+                strip_docs(&mut item.attrs);
+                StripDocs.visit_item_enum_mut(&mut item);
+                out.push(item.to_token_stream().to_string());
+            }
+            Item::Const(mut item) if is_ffi(&item.attrs) => {
+                strip_docs(&mut item.attrs);
+                out.push(item.to_token_stream().to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = manifest.join("src");
+
+    println!("cargo:rerun-if-changed=src");
+
+    // the inventory is generated from these items, so any change to an ffi
+    // type or function signature changes the hash, but docs and formatting
+    // don't
+    let mut files = Vec::new();
+    sources(&src, &mut files);
+    files.sort();
+
+    let inventory_hash = files.iter().fold(FNV_OFFSET, |state, path| {
+        let relative = path.strip_prefix(&src).unwrap().to_string_lossy();
+        let contents = fs::read_to_string(path).expect("Failed to read source file");
+        let file = syn::parse_file(&contents).expect("Failed to parse source file");
+        let mut items = Vec::new();
+        ffi_items(file.items, &mut items);
+
+        items
+            .iter()
+            .fold(hash(state, relative.as_bytes()), |state, item| {
+                hash(state, item.as_bytes())
+            })
+    });
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("abi.rs");
+    fs::write(
+        out,
+        format!(
+            "#[allow(clippy::unreadable_literal)]\npub(crate) const INVENTORY_HASH: u64 = {inventory_hash:#018x};\n"
+        ),
+    )
+    .expect("Failed to write abi.rs");
+}
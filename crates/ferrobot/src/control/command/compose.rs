@@ -0,0 +1,380 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{Command, Interruption, Requirements, scheduler};
+use crate::{
+    Runtime,
+    control::event::{AsyncCallback, Emitter, Event},
+    prelude::*,
+};
+
+/// FPGA time of the current tick, in microseconds
+fn now() -> u64 {
+    Runtime::current().scheduler().now()
+}
+
+/// The tick time `duration` after the current one
+fn deadline_after(duration: Duration) -> u64 {
+    now().saturating_add(duration.as_micros().try_into().unwrap_or(u64::MAX))
+}
+
+/// A command of any type, e.g. to compose commands of different types with
+/// [`sequence!`](crate::sequence) or [`parallel!`](crate::parallel)
+pub struct BoxedCommand(Box<dyn scheduler::DynCommand>);
+
+impl BoxedCommand {
+    pub(crate) fn into_inner(self) -> Box<dyn scheduler::DynCommand> {
+        self.0
+    }
+}
+
+impl fmt::Debug for BoxedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoxedCommand").field(&self.0.name()).finish()
+    }
+}
+
+impl Command for BoxedCommand {
+    async fn initialize(&mut self) {
+        self.0.initialize().await;
+    }
+
+    async fn execute(&mut self) {
+        self.0.execute().await;
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.0.is_finished()
+    }
+
+    async fn end(&mut self, interrupted: bool) {
+        self.0.end(interrupted).await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.0.requirements()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.0.interruption()
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+}
+
+/// Combinators on every [`Command`]
+pub trait CommandExt: Command + Sized {
+    #[must_use]
+    fn boxed(self) -> BoxedCommand {
+        BoxedCommand(Box::new(self))
+    }
+
+    /// Interrupt the command if it hasn't finished within `timeout` of robot
+    /// time, checked on each tick
+    #[must_use]
+    fn with_timeout(self, timeout: Duration) -> Timeout<Self> {
+        Timeout {
+            command: self,
+            duration: timeout,
+            deadline: None,
+            finished: false,
+        }
+    }
+
+    /// Interrupt the command once `condition` returns true. The condition is
+    /// checked every tick, after the command executes.
+    #[must_use]
+    fn until<F: FnMut() -> bool + Send + 'static>(self, condition: F) -> Until<Self, F> {
+        Until {
+            command: self,
+            condition,
+            finished: false,
+        }
+    }
+
+    /// Run the command again each time it finishes, until interrupted
+    #[must_use]
+    fn repeat(self) -> Repeat<Self> {
+        Repeat {
+            command: self,
+            running: false,
+        }
+    }
+}
+
+impl<C: Command> CommandExt for C {}
+
+/// A command interrupted after a timeout. Created by
+/// [`CommandExt::with_timeout`].
+pub struct Timeout<C> {
+    command: C,
+    duration: Duration,
+    /// FPGA time the command times out, in microseconds
+    deadline: Option<u64>,
+    finished: bool,
+}
+
+impl<C: Command> Command for Timeout<C> {
+    async fn initialize(&mut self) {
+        self.deadline = Some(deadline_after(self.duration));
+        self.finished = false;
+        self.command.initialize().await;
+    }
+
+    async fn execute(&mut self) {
+        self.command.execute().await;
+        self.finished = self.command.is_finished();
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.finished || self.deadline.is_some_and(|deadline| now() >= deadline)
+    }
+
+    async fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted || !self.finished).await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.command.requirements()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.command.interruption()
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+}
+
+/// A command interrupted once a condition holds. Created by
+/// [`CommandExt::until`].
+pub struct Until<C, F> {
+    command: C,
+    condition: F,
+    finished: bool,
+}
+
+impl<C, F> Command for Until<C, F>
+where
+    C: Command,
+    F: FnMut() -> bool + Send + 'static,
+{
+    async fn initialize(&mut self) {
+        self.finished = false;
+        self.command.initialize().await;
+    }
+
+    async fn execute(&mut self) {
+        self.command.execute().await;
+        self.finished = self.command.is_finished();
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.finished || (self.condition)()
+    }
+
+    async fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted || !self.finished).await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.command.requirements()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.command.interruption()
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+}
+
+/// A command run again each time it finishes. Created by
+/// [`CommandExt::repeat`].
+pub struct Repeat<C> {
+    command: C,
+    running: bool,
+}
+
+impl<C: Command> Command for Repeat<C> {
+    async fn initialize(&mut self) {
+        self.command.initialize().await;
+        self.running = true;
+    }
+
+    async fn execute(&mut self) {
+        if !self.running {
+            self.command.initialize().await;
+            self.running = true;
+        }
+
+        self.command.execute().await;
+
+        if self.command.is_finished() {
+            self.command.end(false).await;
+            self.running = false;
+        }
+    }
+
+    async fn end(&mut self, interrupted: bool) {
+        if self.running {
+            self.command.end(interrupted).await;
+            self.running = false;
+        }
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.command.requirements()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.command.interruption()
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+}
+
+/// A command that does nothing for a duration. Created by [`wait`].
+#[derive(Debug)]
+pub struct Wait {
+    duration: Duration,
+    /// FPGA time the wait is over, in microseconds
+    deadline: Option<u64>,
+}
+
+impl Command for Wait {
+    async fn initialize(&mut self) {
+        self.deadline = Some(deadline_after(self.duration));
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.deadline.is_some_and(|deadline| now() >= deadline)
+    }
+}
+
+/// A command that finishes on the first tick at least `duration` of robot
+/// time after it starts
+#[must_use]
+pub fn wait(duration: Duration) -> Wait {
+    Wait {
+        duration,
+        deadline: None,
+    }
+}
+
+type Predicate<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// A command that finishes once an event is emitted. Created by
+/// [`wait_until`].
+pub struct WaitUntil<E: Event> {
+    event: Arc<E>,
+    predicate: Predicate<E::Data>,
+    matched: Arc<AtomicBool>,
+    registration: Option<(Arc<Emitter>, AsyncCallback)>,
+}
+
+impl<E: Event> WaitUntil<E> {
+    /// Only finish once the event is emitted with data matching `predicate`,
+    /// e.g. once a motor's position is within tolerance of its setpoint
+    #[must_use]
+    pub fn matching(
+        mut self,
+        predicate: impl Fn(&E::Data) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Arc::new(predicate);
+        self
+    }
+}
+
+impl<E: Event> Command for WaitUntil<E> {
+    async fn initialize(&mut self) {
+        // a fresh flag, so a callback from a previous run can't finish this one
+        let matched = Arc::new(AtomicBool::new(false));
+        let predicate = Arc::clone(&self.predicate);
+        let emitter = Emitter::current();
+
+        self.matched = Arc::clone(&matched);
+
+        let callback = emitter
+            .register(
+                &self.event,
+                Arc::new(move |data: Arc<E::Data>| {
+                    if predicate(&data) {
+                        matched.store(true, Ordering::Release);
+                    }
+
+                    Box::pin(async {})
+                }),
+            )
+            .await;
+
+        self.registration = Some((emitter, callback));
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.matched.load(Ordering::Acquire)
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        if let Some((emitter, callback)) = self.registration.take() {
+            emitter.unregister_callback(&*self.event, &callback).await;
+        }
+    }
+}
+
+/// A command that finishes the next time `event` is emitted after it starts
+#[must_use]
+pub fn wait_until<E: Event>(event: &Arc<E>) -> WaitUntil<E> {
+    WaitUntil {
+        event: Arc::clone(event),
+        predicate: Arc::new(|_| true),
+        matched: Arc::new(AtomicBool::new(false)),
+        registration: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::command::{CommandScheduler, Outcome};
+
+    #[test]
+    fn waits_and_timeouts_follow_robot_time() {
+        let runtime = Runtime::with_handler(Arc::new(|_| None));
+
+        let outcomes = runtime.block_on(async {
+            let scheduler = CommandScheduler::current();
+            let wait = scheduler.schedule(wait(Duration::from_millis(50)));
+            let timeout = scheduler.schedule(
+                super::wait(Duration::from_secs(10)).with_timeout(Duration::from_millis(50)),
+            );
+            let mut outcomes = Vec::new();
+
+            for timestamp in [1_000_000, 1_020_000, 1_040_000, 1_060_000] {
+                runtime.tick(timestamp).await;
+                outcomes.push((wait.outcome(), timeout.outcome()));
+            }
+
+            outcomes
+        });
+
+        assert_eq!(
+            outcomes,
+            [
+                (None, None),
+                (None, None),
+                (None, None),
+                (Some(Outcome::Finished), Some(Outcome::Finished)),
+            ]
+        );
+    }
+}
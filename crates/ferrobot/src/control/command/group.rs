@@ -0,0 +1,570 @@
+use std::convert;
+
+use futures::future;
+
+use super::{BoxedCommand, Command, CommandExt, Interruption, Requirements, scheduler::DynCommand};
+
+/// A command in a group, tracking whether it has been started and not yet
+/// ended, so every command the group starts is ended exactly once
+struct Child {
+    command: Box<dyn DynCommand>,
+    running: bool,
+}
+
+impl Child {
+    async fn start(&mut self) {
+        self.command.initialize().await;
+        self.running = true;
+    }
+
+    /// Execute the command if it's running, ending it if it finishes.
+    /// Returns whether it's still running.
+    async fn step(&mut self) -> bool {
+        if self.running {
+            self.command.execute().await;
+
+            if self.command.is_finished() {
+                self.command.end(false).await;
+                self.running = false;
+            }
+        }
+
+        self.running
+    }
+
+    async fn interrupt(&mut self) {
+        if self.running {
+            self.command.end(true).await;
+            self.running = false;
+        }
+    }
+}
+
+/// The commands of a group, along with the requirements and interruption
+/// behavior of the group as a whole
+struct Group {
+    children: Vec<Child>,
+    requirements: Requirements,
+    interruption: Interruption,
+}
+
+impl Group {
+    /// Commands that run at the same time can't share a subsystem, as they
+    /// would fight over it
+    #[track_caller]
+    fn new(commands: Vec<BoxedCommand>, concurrent: bool) -> Self {
+        let commands = commands
+            .into_iter()
+            .map(BoxedCommand::into_inner)
+            .collect::<Vec<_>>();
+        let mut requirements = Requirements::new();
+        let mut interruption = Interruption::CancelSelf;
+
+        for command in &commands {
+            let required = command.requirements();
+
+            assert!(
+                !concurrent || !requirements.overlaps(&required),
+                "Commands run in parallel can't require the same subsystem, but {} does",
+                command.name()
+            );
+
+            requirements = requirements.union(&required);
+
+            if command.interruption() == Interruption::CancelIncoming {
+                interruption = Interruption::CancelIncoming;
+            }
+        }
+
+        Self {
+            children: commands
+                .into_iter()
+                .map(|command| Child {
+                    command,
+                    running: false,
+                })
+                .collect(),
+            requirements,
+            interruption,
+        }
+    }
+
+    /// Start every command at once, so the device commands they issue are
+    /// handled together
+    async fn start_all(&mut self) {
+        future::join_all(self.children.iter_mut().map(Child::start)).await;
+    }
+
+    /// Execute every running command at once. Returns whether any is still
+    /// running.
+    async fn step_all(&mut self) -> bool {
+        future::join_all(self.children.iter_mut().map(Child::step))
+            .await
+            .into_iter()
+            .any(convert::identity)
+    }
+
+    async fn interrupt_all(&mut self) {
+        for child in &mut self.children {
+            child.interrupt().await;
+        }
+    }
+}
+
+/// Commands run one after another. Created by [`sequence!`](crate::sequence).
+pub struct Sequence {
+    group: Group,
+    current: usize,
+}
+
+impl Sequence {
+    #[must_use]
+    pub fn new(commands: Vec<BoxedCommand>) -> Self {
+        Self {
+            group: Group::new(commands, false),
+            current: 0,
+        }
+    }
+}
+
+impl Command for Sequence {
+    async fn initialize(&mut self) {
+        self.current = 0;
+
+        if let Some(child) = self.group.children.first_mut() {
+            child.start().await;
+        }
+    }
+
+    async fn execute(&mut self) {
+        let Some(child) = self.group.children.get_mut(self.current) else {
+            return;
+        };
+
+        if !child.step().await {
+            self.current += 1;
+
+            if let Some(next) = self.group.children.get_mut(self.current) {
+                next.start().await;
+            }
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.current >= self.group.children.len()
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        self.group.interrupt_all().await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.group.requirements.clone()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.group.interruption
+    }
+}
+
+/// Commands run at the same time, finishing once all of them have. Created by
+/// [`parallel!`](crate::parallel).
+pub struct Parallel {
+    group: Group,
+    running: bool,
+}
+
+impl Parallel {
+    /// # Panics
+    /// If two of the commands require the same subsystem
+    #[must_use]
+    #[track_caller]
+    pub fn new(commands: Vec<BoxedCommand>) -> Self {
+        Self {
+            group: Group::new(commands, true),
+            running: false,
+        }
+    }
+}
+
+impl Command for Parallel {
+    async fn initialize(&mut self) {
+        self.group.start_all().await;
+        self.running = true;
+    }
+
+    async fn execute(&mut self) {
+        self.running = self.group.step_all().await;
+    }
+
+    fn is_finished(&mut self) -> bool {
+        !self.running
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        self.group.interrupt_all().await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.group.requirements.clone()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.group.interruption
+    }
+}
+
+/// Commands run at the same time, finishing once any of them has and
+/// interrupting the rest. Created by [`race!`](crate::race).
+pub struct Race {
+    group: Group,
+}
+
+impl Race {
+    /// # Panics
+    /// If two of the commands require the same subsystem
+    #[must_use]
+    #[track_caller]
+    pub fn new(commands: Vec<BoxedCommand>) -> Self {
+        Self {
+            group: Group::new(commands, true),
+        }
+    }
+}
+
+impl Command for Race {
+    async fn initialize(&mut self) {
+        self.group.start_all().await;
+    }
+
+    async fn execute(&mut self) {
+        self.group.step_all().await;
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.group.children.is_empty() || self.group.children.iter().any(|child| !child.running)
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        self.group.interrupt_all().await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.group.requirements.clone()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.group.interruption
+    }
+}
+
+/// Commands run alongside a main command, finishing once the main command has
+/// and interrupting the rest. Created by [`deadline`].
+pub struct Deadline {
+    // the main command is the first child
+    group: Group,
+}
+
+impl Command for Deadline {
+    async fn initialize(&mut self) {
+        self.group.start_all().await;
+    }
+
+    async fn execute(&mut self) {
+        self.group.step_all().await;
+    }
+
+    fn is_finished(&mut self) -> bool {
+        !self.group.children[0].running
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        self.group.interrupt_all().await;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.group.requirements.clone()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.group.interruption
+    }
+}
+
+/// Run `others` alongside `main`, finishing once `main` has and interrupting
+/// any of `others` still running
+///
+/// # Panics
+/// If two of the commands require the same subsystem
+#[must_use]
+#[track_caller]
+pub fn deadline(main: impl Command, others: impl IntoIterator<Item = BoxedCommand>) -> Deadline {
+    let commands = [main.boxed()].into_iter().chain(others).collect();
+
+    Deadline {
+        group: Group::new(commands, true),
+    }
+}
+
+/// Run commands one after another, as a single command requiring all of their
+/// subsystems
+#[macro_export]
+macro_rules! sequence {
+    ($($command:expr),* $(,)?) => {
+        $crate::control::command::Sequence::new(::std::vec![
+            $($crate::control::command::CommandExt::boxed($command)),*
+        ])
+    };
+}
+
+/// Run commands at the same time, finishing once all of them have
+///
+/// # Panics
+/// If two of the commands require the same subsystem
+#[macro_export]
+macro_rules! parallel {
+    ($($command:expr),* $(,)?) => {
+        $crate::control::command::Parallel::new(::std::vec![
+            $($crate::control::command::CommandExt::boxed($command)),*
+        ])
+    };
+}
+
+/// Run commands at the same time, finishing once any of them has and
+/// interrupting the rest
+///
+/// # Panics
+/// If two of the commands require the same subsystem
+#[macro_export]
+macro_rules! race {
+    ($($command:expr),* $(,)?) => {
+        $crate::control::command::Race::new(::std::vec![
+            $($crate::control::command::CommandExt::boxed($command)),*
+        ])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::{
+        Runtime,
+        control::command::{CommandScheduler, Subsystem},
+        device::io::DigitalOutput,
+        ffi::Response,
+    };
+
+    #[derive(Default)]
+    struct Counts {
+        initialized: AtomicUsize,
+        executed: AtomicUsize,
+        ended: AtomicUsize,
+        interrupted: AtomicUsize,
+    }
+
+    impl Counts {
+        fn get(&self) -> [usize; 4] {
+            [
+                self.initialized.load(Ordering::Acquire),
+                self.executed.load(Ordering::Acquire),
+                self.ended.load(Ordering::Acquire),
+                self.interrupted.load(Ordering::Acquire),
+            ]
+        }
+    }
+
+    /// Records how it's run, finishing after executing `runs` times
+    struct Probe {
+        counts: Arc<Counts>,
+        runs: usize,
+        requirements: Requirements,
+    }
+
+    impl Command for Probe {
+        async fn initialize(&mut self) {
+            self.counts.initialized.fetch_add(1, Ordering::AcqRel);
+        }
+
+        async fn execute(&mut self) {
+            self.counts.executed.fetch_add(1, Ordering::AcqRel);
+        }
+
+        fn is_finished(&mut self) -> bool {
+            self.counts.executed.load(Ordering::Acquire) >= self.runs
+        }
+
+        async fn end(&mut self, interrupted: bool) {
+            self.counts.ended.fetch_add(1, Ordering::AcqRel);
+
+            if interrupted {
+                self.counts.interrupted.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+
+        fn requirements(&self) -> Requirements {
+            self.requirements.clone()
+        }
+    }
+
+    struct Arm;
+
+    impl Subsystem for Arm {}
+
+    fn probe(runs: usize) -> (Probe, Arc<Counts>) {
+        let counts = Arc::new(Counts::default());
+        let probe = Probe {
+            counts: Arc::clone(&counts),
+            runs,
+            requirements: Requirements::new(),
+        };
+
+        (probe, counts)
+    }
+
+    /// Run `command` as the scheduler would for at most `ticks` ticks,
+    /// interrupting it if it hasn't finished by then. Returns the number of
+    /// ticks it ran for, if it finished.
+    fn run(command: &mut impl Command, ticks: usize) -> Option<usize> {
+        block_on(async {
+            command.initialize().await;
+
+            for tick in 1..=ticks {
+                command.execute().await;
+
+                if command.is_finished() {
+                    command.end(false).await;
+                    return Some(tick);
+                }
+            }
+
+            command.end(true).await;
+            None
+        })
+    }
+
+    #[test]
+    fn interrupted_sequence_ends_each_started_command_once() {
+        let (first, first_counts) = probe(1);
+        let (second, second_counts) = probe(3);
+        let (third, third_counts) = probe(1);
+        let mut sequence = crate::sequence!(first, second, third);
+
+        assert_eq!(run(&mut sequence, 2), None);
+        assert_eq!(first_counts.get(), [1, 1, 1, 0]);
+        assert_eq!(second_counts.get(), [1, 1, 1, 1]);
+        assert_eq!(third_counts.get(), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn parallel_finishes_once_every_command_has() {
+        let (short, short_counts) = probe(1);
+        let (long, long_counts) = probe(3);
+        let mut parallel = crate::parallel!(short, long);
+
+        assert_eq!(run(&mut parallel, 5), Some(3));
+        assert_eq!(short_counts.get(), [1, 1, 1, 0]);
+        assert_eq!(long_counts.get(), [1, 3, 1, 0]);
+    }
+
+    #[test]
+    fn race_finishes_with_the_first_command_and_interrupts_the_rest() {
+        let (short, short_counts) = probe(1);
+        let (long, long_counts) = probe(3);
+        let mut race = crate::race!(long, short);
+
+        assert_eq!(run(&mut race, 5), Some(1));
+        assert_eq!(short_counts.get(), [1, 1, 1, 0]);
+        assert_eq!(long_counts.get(), [1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn deadline_finishes_with_the_main_command_and_interrupts_the_rest() {
+        let (main, main_counts) = probe(2);
+        let (short, short_counts) = probe(1);
+        let (long, long_counts) = probe(5);
+        let mut deadline = deadline(main, [short.boxed(), long.boxed()]);
+
+        assert_eq!(run(&mut deadline, 5), Some(2));
+        assert_eq!(main_counts.get(), [1, 2, 1, 0]);
+        assert_eq!(short_counts.get(), [1, 1, 1, 0]);
+        assert_eq!(long_counts.get(), [1, 2, 1, 1]);
+    }
+
+    /// Sets a digital output each tick
+    struct Set(Arc<DigitalOutput>);
+
+    impl Command for Set {
+        async fn execute(&mut self) {
+            self.0.set(true).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn parallel_setpoints_are_handled_together() {
+        let batches = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&batches);
+        let runtime = Runtime::with_handler(Arc::new(move |commands| {
+            count.fetch_add(1, Ordering::AcqRel);
+            Some(commands.iter().map(|_| Response::ok()).collect())
+        }));
+
+        runtime.block_on(async {
+            let (a, b, ()) = futures::join!(
+                DigitalOutput::new(0),
+                DigitalOutput::new(1),
+                runtime.tick(0)
+            );
+            let before = batches.load(Ordering::Acquire);
+
+            CommandScheduler::current()
+                .schedule(crate::parallel!(Set(a.unwrap()), Set(b.unwrap())));
+            runtime.tick(20_000).await;
+
+            assert_eq!(batches.load(Ordering::Acquire) - before, 1);
+        });
+    }
+
+    #[test]
+    fn sequence_can_require_the_same_subsystem_twice() {
+        let arm = Arc::new(Arm);
+        let (mut first, _) = probe(1);
+        let (mut second, _) = probe(1);
+        first.requirements.add(&arm);
+        second.requirements.add(&arm);
+
+        let sequence = crate::sequence!(first, second);
+
+        assert!(Command::requirements(&sequence).contains(&arm));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't require the same subsystem")]
+    fn parallel_commands_can_not_require_the_same_subsystem() {
+        let arm = Arc::new(Arm);
+        let (mut first, _) = probe(1);
+        let (mut second, _) = probe(1);
+        first.requirements.add(&arm);
+        second.requirements.add(&arm);
+
+        let _ = crate::parallel!(first, second);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't require the same subsystem")]
+    fn deadline_commands_can_not_require_the_same_subsystem() {
+        let arm = Arc::new(Arm);
+        let (mut main, _) = probe(1);
+        let (mut other, _) = probe(1);
+        main.requirements.add(&arm);
+        other.requirements.add(&arm);
+
+        let _ = deadline(main, [other.boxed()]);
+    }
+}
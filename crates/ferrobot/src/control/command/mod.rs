@@ -0,0 +1,80 @@
+mod compose;
+mod group;
+mod requirements;
+mod run;
+mod scheduler;
+
+use std::any;
+
+pub use compose::{
+    BoxedCommand, CommandExt, Repeat, Timeout, Until, Wait, WaitUntil, wait, wait_until,
+};
+pub use group::{Deadline, Parallel, Race, Sequence, deadline};
+pub use requirements::Requirements;
+pub use run::{RunCommand, run};
+pub use scheduler::{CommandHandle, CommandScheduler, Outcome};
+
+/// What happens to a running command when another command that requires one
+/// of the same subsystems is scheduled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interruption {
+    /// The running command is interrupted, and the new command starts
+    #[default]
+    CancelSelf,
+    /// The new command is rejected
+    CancelIncoming,
+}
+
+/// A mechanism that commands need exclusive use of, e.g. a drivetrain or an
+/// intake. At most one command requiring a subsystem runs at a time.
+pub trait Subsystem: Send + Sync + 'static {
+    /// Called every tick, before any commands run
+    fn periodic(&self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    fn name(&self) -> &str {
+        any::type_name::<Self>()
+    }
+}
+
+/// An action run by the [`CommandScheduler`]. Once scheduled, `initialize` is
+/// called, then `execute` every tick until `is_finished` returns true, then
+/// `end`. If the command is interrupted, `end` is called with `interrupted`
+/// set instead.
+///
+/// For a command that is a single async function, see [`run`]. Commands are
+/// composed with [`CommandExt`], [`deadline`], and the
+/// [`sequence!`](crate::sequence), [`parallel!`](crate::parallel) and
+/// [`race!`](crate::race) macros.
+pub trait Command: Send + 'static {
+    fn initialize(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    fn execute(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    fn is_finished(&mut self) -> bool {
+        false
+    }
+
+    fn end(&mut self, interrupted: bool) -> impl Future<Output = ()> + Send {
+        let _ = interrupted;
+        async {}
+    }
+
+    /// The subsystems the command needs exclusive use of
+    fn requirements(&self) -> Requirements {
+        Requirements::new()
+    }
+
+    fn interruption(&self) -> Interruption {
+        Interruption::CancelSelf
+    }
+
+    fn name(&self) -> &str {
+        any::type_name::<Self>()
+    }
+}
@@ -0,0 +1,84 @@
+use std::{any::Any, fmt};
+
+use super::Subsystem;
+use crate::prelude::*;
+
+/// The subsystems a command needs exclusive use of. Subsystems are compared
+/// by identity, not by value.
+#[derive(Clone, Default)]
+pub struct Requirements {
+    // held strongly so the address of a subsystem can't be reused while a
+    // command requires it
+    subsystems: Vec<Arc<dyn Any + Send + Sync>>,
+}
+
+impl Requirements {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with<S: Subsystem>(mut self, subsystem: &Arc<S>) -> Self {
+        self.add(subsystem);
+        self
+    }
+
+    pub fn add<S: Subsystem>(&mut self, subsystem: &Arc<S>) {
+        let subsystem = Arc::clone(subsystem) as Arc<dyn Any + Send + Sync>;
+
+        if !self.contains_id(id(&subsystem)) {
+            self.subsystems.push(subsystem);
+        }
+    }
+
+    /// Every subsystem required by either `self` or `other`
+    #[must_use]
+    pub fn union(&self, other: &Requirements) -> Self {
+        let mut union = self.clone();
+
+        for subsystem in &other.subsystems {
+            if !union.contains_id(id(subsystem)) {
+                union.subsystems.push(Arc::clone(subsystem));
+            }
+        }
+
+        union
+    }
+
+    #[must_use]
+    pub fn contains<S: Subsystem>(&self, subsystem: &Arc<S>) -> bool {
+        self.contains_id(Arc::as_ptr(subsystem).addr())
+    }
+
+    /// Whether any subsystem is required by both `self` and `other`
+    #[must_use]
+    pub fn overlaps(&self, other: &Requirements) -> bool {
+        other.ids().any(|id| self.contains_id(id))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.subsystems.is_empty()
+    }
+
+    pub(crate) fn contains_id(&self, id: usize) -> bool {
+        self.ids().any(|other| other == id)
+    }
+
+    pub(crate) fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.subsystems.iter().map(id)
+    }
+}
+
+impl fmt::Debug for Requirements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.ids().map(|id| format!("{id:#x}")))
+            .finish()
+    }
+}
+
+fn id(subsystem: &Arc<dyn Any + Send + Sync>) -> usize {
+    Arc::as_ptr(subsystem).cast::<()>().addr()
+}
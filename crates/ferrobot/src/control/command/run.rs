@@ -0,0 +1,160 @@
+use std::task::Poll;
+
+use futures::{
+    FutureExt,
+    future::{self, BoxFuture},
+};
+
+use super::{Command, Interruption, Requirements, Subsystem};
+use crate::{Runtime, prelude::*};
+
+/// A command that runs an async function to completion, stepping it each tick
+/// alongside the other commands, so the device commands it issues are handled
+/// in the tick's batch. Interrupting the command drops the function at its
+/// current await point.
+pub struct RunCommand<F> {
+    f: F,
+    requirements: Requirements,
+    interruption: Interruption,
+    future: Option<BoxFuture<'static, ()>>,
+    done: bool,
+}
+
+impl<F> RunCommand<F> {
+    /// Require `subsystem`, so no other command uses it while this runs
+    #[must_use]
+    pub fn requiring<S: Subsystem>(mut self, subsystem: &Arc<S>) -> Self {
+        self.requirements.add(subsystem);
+        self
+    }
+
+    #[must_use]
+    pub fn with_interruption(mut self, interruption: Interruption) -> Self {
+        self.interruption = interruption;
+        self
+    }
+}
+
+impl<F, Fut> Command for RunCommand<F>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    async fn initialize(&mut self) {
+        self.future = Some(Box::pin((self.f)()));
+        self.done = false;
+    }
+
+    /// Run the function until it completes, or waits on something other than
+    /// the device commands it issued this tick
+    async fn execute(&mut self) {
+        let Some(future) = self.future.as_mut() else {
+            return;
+        };
+        let devices = Arc::clone(Runtime::current().devices());
+
+        let done = future::poll_fn(|cx| match future.poll_unpin(cx) {
+            Poll::Ready(()) => Poll::Ready(true),
+            // the tick hands queued commands to the robot once everything in
+            // it is waiting, then polls again
+            Poll::Pending if devices.has_queued() => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Ready(false),
+        })
+        .await;
+
+        if done {
+            self.future = None;
+            self.done = true;
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.done
+    }
+
+    async fn end(&mut self, _interrupted: bool) {
+        self.future = None;
+    }
+
+    fn requirements(&self) -> Requirements {
+        self.requirements.clone()
+    }
+
+    fn interruption(&self) -> Interruption {
+        self.interruption
+    }
+}
+
+/// A command that runs `f` each time it is scheduled, finishing when the
+/// future completes
+pub fn run<F, Fut>(f: F) -> RunCommand<F>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    RunCommand {
+        f,
+        requirements: Requirements::new(),
+        interruption: Interruption::default(),
+        future: None,
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        control::command::{CommandScheduler, Outcome},
+        device::io::DigitalOutput,
+        ffi::Response,
+    };
+
+    #[test]
+    fn function_runs_within_the_tick_and_is_dropped_on_interrupt() {
+        let runtime = Runtime::with_handler(Arc::new(|commands| {
+            Some(commands.iter().map(|_| Response::ok()).collect())
+        }));
+        let steps = Arc::new(AtomicUsize::new(0));
+        let alive = Arc::new(());
+
+        let outcome = runtime.block_on(async {
+            let (output, ()) = futures::join!(DigitalOutput::new(0), runtime.tick(0));
+            let output = output.unwrap();
+            let (count, held) = (Arc::clone(&steps), Arc::clone(&alive));
+
+            let handle = CommandScheduler::current().schedule(run(move || {
+                let (output, count, held) =
+                    (Arc::clone(&output), Arc::clone(&count), Arc::clone(&held));
+
+                async move {
+                    let _ = &held;
+
+                    for value in [true, false] {
+                        output.set(value).await.unwrap();
+                        count.fetch_add(1, Ordering::AcqRel);
+                    }
+
+                    future::pending::<()>().await;
+                }
+            }));
+
+            // both setpoints are handled in the tick the command starts in
+            runtime.tick(20_000).await;
+            assert_eq!(steps.load(Ordering::Acquire), 2);
+
+            handle.cancel();
+            runtime.tick(40_000).await;
+            handle.outcome()
+        });
+
+        assert_eq!(outcome, Some(Outcome::Interrupted));
+        // the command and its function are gone, along with what they held
+        assert_eq!(Arc::strong_count(&alive), 1);
+    }
+}
@@ -0,0 +1,496 @@
+use std::{
+    mem,
+    sync::{
+        Mutex, MutexGuard, PoisonError, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use async_lock::Mutex as AsyncMutex;
+use futures::{
+    FutureExt,
+    channel::oneshot,
+    future::{self, BoxFuture, Shared},
+};
+
+use super::{Command, Interruption, Requirements, Subsystem};
+use crate::{
+    Runtime,
+    error::{Error, ErrorKind, Result},
+    prelude::*,
+};
+
+/// Object-safe form of [`Command`], so commands of different types can be
+/// stored together
+pub(crate) trait DynCommand: Send {
+    fn initialize(&mut self) -> BoxFuture<'_, ()>;
+    fn execute(&mut self) -> BoxFuture<'_, ()>;
+    fn is_finished(&mut self) -> bool;
+    fn end(&mut self, interrupted: bool) -> BoxFuture<'_, ()>;
+    fn requirements(&self) -> Requirements;
+    fn interruption(&self) -> Interruption;
+    fn name(&self) -> &str;
+}
+
+impl<C: Command> DynCommand for C {
+    fn initialize(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(Command::initialize(self))
+    }
+
+    fn execute(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(Command::execute(self))
+    }
+
+    fn is_finished(&mut self) -> bool {
+        Command::is_finished(self)
+    }
+
+    fn end(&mut self, interrupted: bool) -> BoxFuture<'_, ()> {
+        Box::pin(Command::end(self, interrupted))
+    }
+
+    fn requirements(&self) -> Requirements {
+        Command::requirements(self)
+    }
+
+    fn interruption(&self) -> Interruption {
+        Command::interruption(self)
+    }
+
+    fn name(&self) -> &str {
+        Command::name(self)
+    }
+}
+
+trait DynSubsystem: Send + Sync {
+    fn periodic(&self) -> BoxFuture<'_, ()>;
+}
+
+impl<S: Subsystem> DynSubsystem for S {
+    fn periodic(&self) -> BoxFuture<'_, ()> {
+        Box::pin(Subsystem::periodic(self))
+    }
+}
+
+/// How a scheduled command stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Finished,
+    /// Cancelled, or interrupted by another command requiring one of its
+    /// subsystems
+    Interrupted,
+    /// Never started, as a subsystem it requires is in use by a command that
+    /// can't be interrupted
+    Rejected,
+}
+
+struct Queued {
+    id: u64,
+    command: Box<dyn DynCommand>,
+    outcome: oneshot::Sender<Outcome>,
+}
+
+struct Running {
+    id: u64,
+    command: Box<dyn DynCommand>,
+    requirements: Requirements,
+    interruption: Interruption,
+    /// The subsystem this is the default command of
+    default_of: Option<usize>,
+    outcome: Option<oneshot::Sender<Outcome>>,
+}
+
+struct DefaultCommand {
+    subsystem: usize,
+    /// `None` while the command is running
+    command: Option<Box<dyn DynCommand>>,
+}
+
+/// Changes requested since the last tick, applied at the start of the next.
+/// Kept apart from [`State`] so they can be requested from inside a command.
+#[derive(Default)]
+struct Pending {
+    subsystems: Vec<Arc<dyn DynSubsystem>>,
+    defaults: Vec<(usize, Box<dyn DynCommand>)>,
+    scheduled: Vec<Queued>,
+    cancelled: Vec<u64>,
+    cancel_all: bool,
+}
+
+#[derive(Default)]
+struct State {
+    subsystems: Vec<Arc<dyn DynSubsystem>>,
+    defaults: Vec<DefaultCommand>,
+    running: Vec<Running>,
+}
+
+/// Runs commands on each robot tick, making sure no two running commands
+/// require the same subsystem. Each tick, registered subsystems' `periodic`
+/// runs, then newly scheduled commands start, then every running command
+/// executes concurrently, then those that finished end in the order they were
+/// scheduled, then default commands start on any subsystem left unused.
+pub struct CommandScheduler {
+    pending: Mutex<Pending>,
+    state: AsyncMutex<State>,
+    next_id: AtomicU64,
+}
+
+impl CommandScheduler {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Mutex::new(Pending::default()),
+            state: AsyncMutex::new(State::default()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// The command scheduler of the current runtime
+    #[must_use]
+    pub fn current() -> Arc<Self> {
+        Arc::clone(Runtime::current().commands())
+    }
+
+    /// Start `command` on the next tick, interrupting any running commands
+    /// that require its subsystems
+    pub fn schedule(self: &Arc<Self>, command: impl Command) -> CommandHandle {
+        self.schedule_boxed(Box::new(command))
+    }
+
+    pub(crate) fn schedule_boxed(self: &Arc<Self>, command: Box<dyn DynCommand>) -> CommandHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+
+        self.pending().scheduled.push(Queued {
+            id,
+            command,
+            outcome: tx,
+        });
+
+        CommandHandle {
+            id,
+            scheduler: Arc::downgrade(self),
+            outcome: rx.shared(),
+        }
+    }
+
+    /// Call `subsystem`'s `periodic` every tick
+    pub fn register<S: Subsystem>(&self, subsystem: &Arc<S>) {
+        self.pending()
+            .subsystems
+            .push(Arc::clone(subsystem) as Arc<dyn DynSubsystem>);
+    }
+
+    /// Run `command` whenever no other command requires `subsystem`. The
+    /// command must require `subsystem`, and is initialized again each time
+    /// it starts.
+    ///
+    /// # Errors
+    /// [`ErrorKind::InvalidArgument`] if `command` doesn't require `subsystem`
+    pub fn set_default_command<S: Subsystem>(
+        &self,
+        subsystem: &Arc<S>,
+        command: impl Command,
+    ) -> Result<()> {
+        if !command.requirements().contains(subsystem) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Default command {} of {} must require it",
+                    command.name(),
+                    subsystem.name()
+                ),
+            ));
+        }
+
+        self.pending()
+            .defaults
+            .push((Arc::as_ptr(subsystem).addr(), Box::new(command)));
+
+        Ok(())
+    }
+
+    /// Interrupt every running command
+    pub fn cancel_all(&self) {
+        self.pending().cancel_all = true;
+    }
+
+    fn cancel(&self, id: u64) {
+        self.pending().cancelled.push(id);
+    }
+
+    fn pending(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) async fn tick(&self) {
+        let mut state = self.state.lock().await;
+        let pending = mem::take(&mut *self.pending());
+
+        state.subsystems.extend(pending.subsystems);
+
+        for (subsystem, command) in pending.defaults {
+            state
+                .defaults
+                .retain(|default| default.subsystem != subsystem);
+            state.defaults.push(DefaultCommand {
+                subsystem,
+                command: Some(command),
+            });
+        }
+
+        for subsystem in &state.subsystems {
+            subsystem.periodic().await;
+        }
+
+        if pending.cancel_all {
+            for running in mem::take(&mut state.running) {
+                state.stop(running, Outcome::Interrupted).await;
+            }
+        }
+
+        for id in &pending.cancelled {
+            let idx = state.running.iter().position(|running| running.id == *id);
+
+            if let Some(idx) = idx {
+                let running = state.running.remove(idx);
+                state.stop(running, Outcome::Interrupted).await;
+            }
+        }
+
+        for queued in pending.scheduled {
+            if pending.cancelled.contains(&queued.id) {
+                let _ = queued.outcome.send(Outcome::Interrupted);
+                continue;
+            }
+
+            let requirements = queued.command.requirements();
+            state
+                .start(
+                    queued.id,
+                    queued.command,
+                    requirements,
+                    Some(queued.outcome),
+                    None,
+                )
+                .await;
+        }
+
+        // executed together, so the device commands they issue are handled in
+        // one batch rather than each waiting on the last
+        future::join_all(
+            state
+                .running
+                .iter_mut()
+                .map(|running| running.command.execute()),
+        )
+        .await;
+
+        let mut idx = 0;
+        while idx < state.running.len() {
+            if state.running[idx].command.is_finished() {
+                let running = state.running.remove(idx);
+                state.stop(running, Outcome::Finished).await;
+            } else {
+                idx += 1;
+            }
+        }
+
+        let mut idx = 0;
+        while idx < state.defaults.len() {
+            let subsystem = state.defaults[idx].subsystem;
+            let in_use = state
+                .running
+                .iter()
+                .any(|running| running.requirements.contains_id(subsystem));
+
+            if let Some(command) = state.defaults[idx].command.take_if(|_| !in_use) {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                let requirements = command.requirements();
+                state
+                    .start(id, command, requirements, None, Some(subsystem))
+                    .await;
+            }
+
+            idx += 1;
+        }
+    }
+}
+
+impl State {
+    async fn start(
+        &mut self,
+        id: u64,
+        mut command: Box<dyn DynCommand>,
+        requirements: Requirements,
+        outcome: Option<oneshot::Sender<Outcome>>,
+        default_of: Option<usize>,
+    ) {
+        let conflicts = self
+            .running
+            .iter()
+            .filter(|running| running.requirements.overlaps(&requirements))
+            .map(|running| running.id)
+            .collect::<Vec<_>>();
+        let blocking = self
+            .running
+            .iter()
+            .filter(|running| conflicts.contains(&running.id))
+            .find(|running| running.interruption == Interruption::CancelIncoming)
+            .map(|running| running.command.name().to_owned());
+
+        if let Some(blocking) = blocking {
+            debug!(
+                "Rejected command {}, as {blocking} can't be interrupted",
+                command.name()
+            );
+
+            if let Some(outcome) = outcome {
+                let _ = outcome.send(Outcome::Rejected);
+            }
+
+            if let Some(subsystem) = default_of {
+                self.restore_default(subsystem, command);
+            }
+
+            return;
+        }
+
+        for id in conflicts {
+            let idx = self
+                .running
+                .iter()
+                .position(|running| running.id == id)
+                .expect("Interrupted command is running");
+            let running = self.running.remove(idx);
+
+            debug!(
+                "Interrupting command {} for {}",
+                running.command.name(),
+                command.name()
+            );
+            self.stop(running, Outcome::Interrupted).await;
+        }
+
+        command.initialize().await;
+
+        let interruption = command.interruption();
+        self.running.push(Running {
+            id,
+            command,
+            requirements,
+            interruption,
+            default_of,
+            outcome,
+        });
+    }
+
+    async fn stop(&mut self, mut running: Running, outcome: Outcome) {
+        running.command.end(outcome == Outcome::Interrupted).await;
+
+        if let Some(tx) = running.outcome {
+            let _ = tx.send(outcome);
+        }
+
+        if let Some(subsystem) = running.default_of {
+            self.restore_default(subsystem, running.command);
+        }
+    }
+
+    /// Hand a default command back to its subsystem, unless it has been
+    /// replaced since it started
+    fn restore_default(&mut self, subsystem: usize, command: Box<dyn DynCommand>) {
+        if let Some(default) = self
+            .defaults
+            .iter_mut()
+            .find(|default| default.subsystem == subsystem && default.command.is_none())
+        {
+            default.command = Some(command);
+        }
+    }
+}
+
+/// A command scheduled with [`CommandScheduler::schedule`]
+#[derive(Clone)]
+pub struct CommandHandle {
+    id: u64,
+    scheduler: Weak<CommandScheduler>,
+    outcome: Shared<oneshot::Receiver<Outcome>>,
+}
+
+impl CommandHandle {
+    /// Interrupt the command on the next tick, or stop it from starting
+    pub fn cancel(&self) {
+        if let Some(scheduler) = self.scheduler.upgrade() {
+            scheduler.cancel(self.id);
+        }
+    }
+
+    /// How the command stopped, or `None` if it hasn't yet
+    #[must_use]
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+            .clone()
+            .now_or_never()
+            .map(|outcome| outcome.unwrap_or(Outcome::Interrupted))
+    }
+
+    /// Wait for the command to stop
+    pub async fn finished(&self) -> Outcome {
+        self.outcome.clone().await.unwrap_or(Outcome::Interrupted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::{device::io::DigitalOutput, ffi::Response, schedule};
+
+    /// Creates a digital output, then toggles it every tick
+    struct Toggle {
+        output: Option<Arc<DigitalOutput>>,
+        executed: Arc<AtomicUsize>,
+    }
+
+    impl Command for Toggle {
+        async fn initialize(&mut self) {
+            self.output = Some(DigitalOutput::new(0).await.unwrap());
+        }
+
+        async fn execute(&mut self) {
+            let executed = self.executed.fetch_add(1, Ordering::AcqRel) + 1;
+            let output = self.output.as_ref().unwrap();
+
+            output.set(executed.is_multiple_of(2)).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn setpoints_are_handled_within_the_tick() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&handled);
+        let runtime = Runtime::with_handler(Arc::new(move |commands| {
+            count.fetch_add(commands.len(), Ordering::AcqRel);
+            Some(commands.iter().map(|_| Response::ok()).collect())
+        }));
+        let executed = Arc::new(AtomicUsize::new(0));
+
+        let overruns = runtime.block_on(async {
+            CommandScheduler::current().schedule(Toggle {
+                output: None,
+                executed: Arc::clone(&executed),
+            });
+
+            runtime.tick(0).await;
+            runtime.tick(20_000).await;
+
+            schedule::loop_stats().overruns
+        });
+
+        assert_eq!(executed.load(Ordering::Acquire), 2);
+        // the output's creation, then a setpoint each tick
+        assert_eq!(handled.load(Ordering::Acquire), 3);
+        assert_eq!(overruns, 0);
+    }
+}
@@ -3,14 +3,14 @@ use std::{
     collections::HashMap,
     convert,
     ffi::c_void,
-    fmt,
-    sync::LazyLock,
+    fmt, ptr,
+    sync::Weak,
 };
 
-use async_std::sync::RwLock;
+use async_lock::RwLock;
 use futures::future::{self, BoxFuture};
 
-use crate::{device::Device, prelude::*};
+use crate::{Runtime, device::Device, prelude::*};
 
 // to make a future send-sync even when rust says it isn't
 // (i used a raw ptr that one time and it crashes out)
@@ -20,47 +20,53 @@ struct ArcPtr {
 }
 
 impl ArcPtr {
-    fn new<T>(arc: Arc<T>) -> Self {
+    fn new<T>(value: &T) -> Self {
         Self {
-            ptr: Arc::into_raw(arc).cast(),
+            ptr: ptr::from_ref(value).cast(),
         }
     }
-
-    unsafe fn to_arc<T>(self) -> Arc<T> {
-        unsafe { Arc::from_raw(self.ptr.cast()) }
-    }
 }
 
 unsafe impl Send for ArcPtr {}
 unsafe impl Sync for ArcPtr {}
 
 type ErasedArc = Arc<dyn Any + Send + Sync>;
-type AsyncCallback = Arc<dyn (Fn(ErasedArc) -> BoxFuture<'static, ()>) + Send + Sync>;
-type CallbackMap = HashMap<TypeId, HashMap<ArcPtr, Vec<AsyncCallback>>>;
+pub(crate) type AsyncCallback = Arc<dyn (Fn(ErasedArc) -> BoxFuture<'static, ()>) + Send + Sync>;
+type CallbackMap = HashMap<TypeId, HashMap<ArcPtr, Callbacks>>;
+
+struct Callbacks {
+    /// Held weakly so registering a callback doesn't keep the event (e.g. a
+    /// device) alive. The allocation outlives the event, so its address can't
+    /// be reused by another event while the callbacks are registered.
+    event: Weak<dyn Any + Send + Sync>,
+    callbacks: Vec<AsyncCallback>,
+}
 
 pub(crate) struct Emitter {
     callbacks: Arc<RwLock<CallbackMap>>,
 }
 
 impl Emitter {
-    pub(crate) fn instance() -> &'static Emitter {
-        static INSTANCE: LazyLock<Arc<Emitter>> = LazyLock::new(|| Arc::new(Emitter::new()));
-        &INSTANCE
+    /// The emitter of the current runtime
+    pub(crate) fn current() -> Arc<Emitter> {
+        Arc::clone(Runtime::current().emitter())
     }
 
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             callbacks: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Register `callback` on `event`, returning the registered callback so
+    /// it can be removed with [`Emitter::unregister_callback`]
     pub(crate) async fn register<E: Event + 'static>(
         &self,
         event: &Arc<E>,
         callback: Arc<dyn (Fn(Arc<E::Data>) -> BoxFuture<'static, ()>) + Send + Sync>,
-    ) {
+    ) -> AsyncCallback {
         let type_id = TypeId::of::<E>();
-        let event_ptr = ArcPtr::new(Arc::clone(event));
+        let event_ptr = ArcPtr::new(&**event);
         let callback = Arc::new(move |data: Arc<dyn Any + Send + Sync>| {
             callback(unsafe { Arc::downcast_unchecked(data) })
         }) as AsyncCallback;
@@ -68,11 +74,45 @@ impl Emitter {
         let mut callbacks = self.callbacks.write().await;
         let entry = callbacks.entry(type_id).or_default();
 
-        if let Some(callbacks) = entry.get_mut(&event_ptr) {
-            callbacks.push(callback);
-            unsafe { drop(event_ptr.to_arc::<E>()) } // this ptr can die, since we already leaked it
-        } else {
-            entry.insert(event_ptr, vec![callback]); // we can leak event here, since this ptr needs to live
+        // forget about events that have been dropped
+        entry.retain(|_, callbacks| callbacks.event.strong_count() > 0);
+
+        entry
+            .entry(event_ptr)
+            .or_insert_with(|| Callbacks {
+                event: Arc::downgrade(event) as Weak<dyn Any + Send + Sync>,
+                callbacks: Vec::new(),
+            })
+            .callbacks
+            .push(Arc::clone(&callback));
+
+        callback
+    }
+
+    /// Remove every callback registered for `event`
+    pub(crate) async fn unregister<E: Event + 'static>(&self, event: &E) {
+        let mut callbacks = self.callbacks.write().await;
+
+        if let Some(entry) = callbacks.get_mut(&TypeId::of::<E>()) {
+            entry.remove(&ArcPtr::new(event));
+        }
+    }
+
+    /// Remove a single callback returned by [`Emitter::register`]
+    pub(crate) async fn unregister_callback<E: Event + 'static>(
+        &self,
+        event: &E,
+        callback: &AsyncCallback,
+    ) {
+        let mut callbacks = self.callbacks.write().await;
+
+        if let Some(callbacks) = callbacks
+            .get_mut(&TypeId::of::<E>())
+            .and_then(|entry| entry.get_mut(&ArcPtr::new(event)))
+        {
+            callbacks
+                .callbacks
+                .retain(|other| !Arc::ptr_eq(other, callback));
         }
     }
 
@@ -87,27 +127,29 @@ impl Emitter {
             Arc::new(move |data: Arc<Tr::Data>| {
                 let event = Arc::clone(&event);
                 let data = map(data);
-                Box::pin(Emitter::instance().emit(event, data))
+                Box::pin(async move { Emitter::current().emit(event, data).await })
             }),
         )
         .await;
     }
 
-    async fn emit<E: Event + 'static>(&self, event: Arc<E>, data: Arc<E::Data>) {
-        let callbacks = self.callbacks.read().await;
+    pub(crate) async fn emit<E: Event + 'static>(&self, event: Arc<E>, data: Arc<E::Data>) {
         let type_id = TypeId::of::<E>();
-        let event_ptr = ArcPtr::new(event);
+        let event_ptr = ArcPtr::new(&*event);
         let data = data as Arc<dyn Any + Send + Sync>;
 
-        if let Some(entry) = callbacks.get(&type_id) {
-            if let Some(callbacks) = entry.get(&event_ptr) {
-                future::join_all(callbacks.iter().map(|callback| callback(Arc::clone(&data))))
-                    .await;
-            }
-        }
-
-        // drop event_ptr, leak bad
-        unsafe { drop(event_ptr.to_arc::<E>()) }
+        // cloned out, so callbacks can register, unregister or emit without
+        // waiting on the lock they were called under
+        let callbacks = self
+            .callbacks
+            .read()
+            .await
+            .get(&type_id)
+            .and_then(|entry| entry.get(&event_ptr))
+            .map(|callbacks| callbacks.callbacks.clone())
+            .unwrap_or_default();
+
+        future::join_all(callbacks.iter().map(|callback| callback(Arc::clone(&data)))).await;
     }
 
     pub(crate) async fn emit_device<D: Device + 'static>(&self, event: Arc<D>, data: Arc<D::Data>) {
@@ -132,7 +174,7 @@ pub trait EventExt: Event {
         f: F,
         on_err: fn(E),
     ) {
-        Emitter::instance()
+        Emitter::current()
             .register(
                 self,
                 Arc::new(move |data| {
@@ -150,13 +192,13 @@ pub trait EventExt: Event {
         self: &Arc<Self>,
         f: F,
     ) {
-        Emitter::instance()
+        Emitter::current()
             .register(self, Arc::new(move |data| Box::pin(f(data))))
             .await;
     }
 
     async fn trigger<E: Event<Data = Self::Data>>(self: &Arc<Self>, other: &Arc<E>) {
-        Emitter::instance()
+        Emitter::current()
             .register_trigger(self, Arc::clone(other), convert::identity)
             .await;
     }
@@ -167,3 +209,46 @@ impl<T: Event> EventExt for T {}
 impl<D: Device> Event for D {
     type Data = D::Data;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct Ping;
+
+    impl Event for Ping {
+        type Data = ();
+    }
+
+    #[test]
+    fn callbacks_can_unregister_the_event_they_were_called_for() {
+        let runtime = Runtime::with_handler(Arc::new(|_| None));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&calls);
+
+        runtime.block_on(async {
+            let ping = Arc::new(Ping);
+            let event = Arc::clone(&ping);
+            let emitter = Emitter::current();
+
+            emitter
+                .register(
+                    &ping,
+                    Arc::new(move |_| {
+                        count.fetch_add(1, Ordering::AcqRel);
+                        let event = Arc::clone(&event);
+
+                        Box::pin(async move { Emitter::current().unregister(&*event).await })
+                    }),
+                )
+                .await;
+
+            emitter.emit(Arc::clone(&ping), Arc::new(())).await;
+            emitter.emit(ping, Arc::new(())).await;
+        });
+
+        assert_eq!(calls.load(Ordering::Acquire), 1);
+    }
+}
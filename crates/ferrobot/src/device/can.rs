@@ -0,0 +1,95 @@
+use std::sync::LazyLock;
+
+use uom::si::{ratio::ratio, time::microsecond};
+
+use super::prelude::*;
+use crate::control::event::Event;
+
+/// Bus utilization above which a warning is logged
+const HIGH_UTILIZATION: f64 = 0.9;
+
+/// Emitted on every update from the robot with the health of the CAN bus
+#[derive(Debug)]
+pub struct CanBusStatus {
+    _private: (),
+}
+
+impl CanBusStatus {
+    #[must_use]
+    pub fn event() -> Arc<Self> {
+        static INSTANCE: LazyLock<Arc<CanBusStatus>> =
+            LazyLock::new(|| Arc::new(CanBusStatus { _private: () }));
+
+        Arc::clone(&INSTANCE)
+    }
+}
+
+impl Event for CanBusStatus {
+    type Data = CanStatus;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CanStatus {
+    /// Fraction of the bus bandwidth in use
+    pub utilization: Ratio,
+    /// Times the bus has gone off since the robot started, e.g. from a short
+    /// or a missing terminator
+    pub bus_off_count: u32,
+    /// Times a message was dropped because the transmit buffer was full
+    pub tx_full_count: u32,
+    /// The receive error counter, which rises with errors and falls with
+    /// successful messages
+    pub rx_error_count: u32,
+    /// The transmit error counter, which rises with errors and falls with
+    /// successful messages
+    pub tx_error_count: u32,
+    /// FPGA time the status was read
+    pub timestamp: Time,
+}
+
+impl CanStatus {
+    pub(crate) fn new(ffi: &ferrobot_ffi::CanStatus, timestamp: u64) -> Self {
+        Self {
+            utilization: Ratio::new::<ratio>(f64::from(ffi.utilization)),
+            bus_off_count: ffi.bus_off_count,
+            tx_full_count: ffi.tx_full_count,
+            rx_error_count: ffi.rx_error_count,
+            tx_error_count: ffi.tx_error_count,
+            timestamp: Time::new::<microsecond>(timestamp as f64),
+        }
+    }
+
+    /// Log anything that got worse since the `previous` status, so flaky
+    /// wiring shows up in the logs
+    pub(crate) fn log_changes(&self, previous: Option<&CanStatus>) {
+        let high = |status: &CanStatus| status.utilization.get::<ratio>() > HIGH_UTILIZATION;
+
+        if let Some(previous) = previous {
+            if self.bus_off_count > previous.bus_off_count {
+                error!(
+                    "CAN bus went off {} time(s), check wiring and termination",
+                    self.bus_off_count - previous.bus_off_count
+                );
+            }
+
+            if self.tx_full_count > previous.tx_full_count {
+                warn!(
+                    "CAN transmit buffer was full {} time(s)",
+                    self.tx_full_count - previous.tx_full_count
+                );
+            }
+        }
+
+        if high(self) && !previous.is_some_and(high) {
+            warn!(
+                "CAN bus utilization is high: {:.0}%",
+                self.utilization.get::<ratio>() * 100.0
+            );
+        }
+    }
+}
+
+/// The latest CAN bus status supplied by the robot
+pub async fn can_status() -> Option<CanStatus> {
+    DeviceContext::current().can_status().await
+}
@@ -0,0 +1,43 @@
+use typed_builder::TypedBuilder;
+use uom::si::angle::revolution;
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::cancoder::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SensorDirection {
+    /// Counter-clockwise rotation is positive, when looking at the LED side
+    CounterClockwisePositive = 0,
+    /// Clockwise rotation is positive, when looking at the LED side
+    ClockwisePositive = 1,
+}
+
+#[ffi_type(namespace = "ffi::device::cancoder::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct CanCoderConfig {
+    /// Added to the raw magnet position, in rotations
+    #[builder(default = 0.0, setter(transform = |offset: Angle| offset.get::<revolution>()))]
+    pub magnet_offset: f64,
+
+    #[builder(default = SensorDirection::CounterClockwisePositive)]
+    pub sensor_direction: SensorDirection,
+
+    /// Where the absolute position wraps around, in rotations. The default
+    /// of 0.5 reports -0.5 to 0.5 rotations, and 1.0 reports 0 to 1
+    /// rotation.
+    #[builder(default = 0.5, setter(transform = |point: Angle| point.get::<revolution>()))]
+    pub discontinuity_point: f64,
+}
+
+impl Default for CanCoderConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(SensorDirection))
+        .register(extra_type!(CanCoderConfig))
+}
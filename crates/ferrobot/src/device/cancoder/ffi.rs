@@ -0,0 +1,79 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::cancoder")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetPosition,
+    Create,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::cancoder")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_position(position: f64) -> Self {
+        Self {
+            kind: CommandType::SetPosition,
+            data: Box::into_raw(Box::new(position)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create(config: cancoder::CanCoderConfig) -> Self {
+        Self {
+            kind: CommandType::Create,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetPosition => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut f64));
+            },
+            CommandType::Create => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut cancoder::CanCoderConfig));
+            },
+            CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::cancoder")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Data {
+    pub(crate) connected: bool,
+    pub(crate) position: f64,
+    pub(crate) absolute_position: f64,
+    pub(crate) velocity: f64,
+    pub(crate) magnet_health: cancoder::MagnetHealth,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
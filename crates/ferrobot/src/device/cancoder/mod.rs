@@ -0,0 +1,124 @@
+mod config;
+mod ffi;
+pub mod prelude;
+
+pub use config::*;
+use prelude::*;
+use uom::si::{angle::revolution, angular_velocity::revolution_per_second as rps};
+
+/// How well the magnet is positioned relative to the sensor
+#[ffi_type(namespace = "ffi::device::cancoder")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MagnetHealth {
+    /// The magnet could not be read
+    Invalid = 0,
+    /// The magnet is too far or too close, and readings are unreliable
+    Red = 1,
+    /// The magnet is slightly out of range, and readings may be inaccurate
+    Orange = 2,
+    /// The magnet is in range
+    Green = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Data {
+    pub connected: bool,
+    /// Position accumulated across rotations, starting at the absolute
+    /// position on boot
+    pub position: Angle,
+    /// Position within one rotation, after the magnet offset
+    pub absolute_position: Angle,
+    pub velocity: AngularVelocity,
+    pub magnet_health: MagnetHealth,
+}
+
+impl<'a> From<&'a cancoder_ffi::Data> for Data {
+    fn from(value: &'a cancoder_ffi::Data) -> Self {
+        Self {
+            connected: value.connected,
+            position: Angle::new::<revolution>(value.position),
+            absolute_position: Angle::new::<revolution>(value.absolute_position),
+            velocity: AngularVelocity::new::<rps>(value.velocity),
+            magnet_health: value.magnet_health,
+        }
+    }
+}
+
+impl Interpolate for Data {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            connected: self.connected.interpolate(&other.connected, t),
+            position: self.position.interpolate(&other.position, t),
+            absolute_position: device::interpolate_wrapped(
+                self.absolute_position,
+                other.absolute_position,
+                t,
+            ),
+            velocity: self.velocity.interpolate(&other.velocity, t),
+            magnet_health: *device::nearest(&self.magnet_health, &other.magnet_health, t),
+        }
+    }
+}
+
+/// A CTRE `CANcoder` absolute encoder
+#[derive(Debug)]
+pub struct CanCoder {
+    can_id: u8,
+}
+
+impl CanCoder {
+    pub async fn new(can_id: u8, config: CanCoderConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { can_id });
+        let command = cancoder_ffi::Command::create(config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    /// Set the accumulated position. The absolute position is unaffected.
+    pub async fn set_position(&self, position: Angle) -> Result<(), Error> {
+        debug!(
+            "Setting cancoder {} position to {:?}",
+            self.can_id, position
+        );
+
+        let position = position.get::<revolution>();
+        let ctx = DeviceContext::current();
+        let command = cancoder_ffi::Command::set_position(position);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for CanCoder {
+    type CommandFFI = cancoder_ffi::Command;
+    type DataFFI = cancoder_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::CanCoder;
+}
+
+impl device::Device for CanCoder {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.can_id
+    }
+}
+
+impl Drop for CanCoder {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder.register(extra_type!(MagnetHealth))
+}
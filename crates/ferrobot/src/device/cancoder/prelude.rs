@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod cancoder_ffi {
+    pub(crate) use crate::device::cancoder::ffi::*;
+}
+
+pub use crate::device::cancoder::CanCoder;
@@ -1,123 +1,532 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    mem,
+    panic::Location,
+    sync::{
+        Mutex, PoisonError, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
 
-use async_std::{sync::RwLock, task};
-use futures::future::{self, BoxFuture};
-use thiserror::Error;
+use async_lock::RwLock;
+use futures::{
+    channel::oneshot,
+    future::{self, BoxFuture},
+};
+use uom::si::time::microsecond;
 
 use super::prelude::*;
-use crate::{control::event::Emitter, ffi::DeviceDatas};
+use crate::{
+    Error, ErrorKind, Runtime,
+    control::event::Emitter,
+    device::{
+        CommandPolicy, Decode, DeviceStale, History, HistoryConfig, Interpolate, Stale,
+        Timestamped,
+        can::{CanBusStatus, CanStatus},
+    },
+    executor,
+    ffi::DeviceDatas,
+};
+
+/// Default time a device can go without an update before it is stale, in
+/// microseconds
+const DEFAULT_STALE_THRESHOLD: u64 = 100_000;
 
 unsafe extern "C" {
-    fn handle_command(command: *const device_ffi::Command) -> ferrobot_ffi::Response;
+    /// Handle `len` commands in order, writing the response to each into
+    /// `responses`. Weakly linked, so runtimes can run without a robot, e.g.
+    /// in tests.
+    #[linkage = "extern_weak"]
+    static handle_commands: Option<
+        unsafe extern "C" fn(
+            commands: *const device_ffi::Command,
+            len: usize,
+            responses: *mut ferrobot_ffi::Response,
+        ),
+    >;
 }
 
-#[allow(private_bounds, private_interfaces)]
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Device ({0:?} {1}) not found in context")]
-    DeviceNotFound(device_ffi::Type, u8),
+/// Hands a batch of commands to the robot, returning the response to each in
+/// order, or `None` if there is no robot to handle them
+pub(crate) type Handler =
+    Arc<dyn Fn(&[device_ffi::Command]) -> Option<Vec<ferrobot_ffi::Response>> + Send + Sync>;
+
+/// A queued command, and where to send it back along with the robot's
+/// response, so it can be retried
+type Pending = (
+    device_ffi::Command,
+    oneshot::Sender<(device_ffi::Command, Option<ferrobot_ffi::Response>)>,
+);
+
+/// Hand `commands` to the robot the library is linked into
+fn robot_handler(commands: &[device_ffi::Command]) -> Option<Vec<ferrobot_ffi::Response>> {
+    let handle = unsafe { handle_commands }?;
+    let mut responses = Vec::with_capacity(commands.len());
+
+    unsafe {
+        handle(commands.as_ptr(), commands.len(), responses.as_mut_ptr());
+        responses.set_len(commands.len());
+    }
 
-    #[error("Device ({0:?} {1}) already registered")]
-    DeviceAlreadyRegistered(device_ffi::Type, u8),
+    Some(responses)
 }
 
 type Emit = Arc<dyn (Fn(&device_ffi::Data) -> BoxFuture<'static, ()>) + Send + Sync>;
 
+struct Registration {
+    /// The registered device, held weakly so it can be dropped
+    owner: Weak<dyn Any + Send + Sync>,
+    emit: Emit,
+    /// Releases the device on the robot, if it exists there
+    destroy: Option<device_ffi::Command>,
+}
+
 pub(crate) struct DeviceContext {
     data: Arc<RwLock<HashMap<device_ffi::Device, device_ffi::Data>>>,
-    emitters: Arc<RwLock<HashMap<device_ffi::Device, Emit>>>,
+    registrations: Arc<RwLock<HashMap<device_ffi::Device, Registration>>>,
+    history: Arc<RwLock<HashMap<device_ffi::Device, History>>>,
+    stale: Arc<RwLock<HashSet<device_ffi::Device>>>,
+    stale_threshold: AtomicU64,
+    /// Commands waiting for the next robot tick
+    queue: Mutex<Vec<Pending>>,
+    handler: Handler,
+    policies: Mutex<HashMap<device_ffi::Type, CommandPolicy>>,
+    can: RwLock<Option<CanStatus>>,
 }
 
 impl DeviceContext {
-    pub(crate) fn instance() -> &'static DeviceContext {
-        static INSTANCE: LazyLock<Arc<DeviceContext>> = LazyLock::new(DeviceContext::new);
-        &INSTANCE
+    /// The device context of the current runtime
+    pub(crate) fn current() -> Arc<DeviceContext> {
+        Arc::clone(Runtime::current().devices())
+    }
+
+    /// A context handing commands to the robot the library is linked into
+    pub(crate) fn new() -> Arc<Self> {
+        Self::with_handler(Arc::new(robot_handler))
     }
 
-    fn new() -> Arc<Self> {
+    pub(crate) fn with_handler(handler: Handler) -> Arc<Self> {
         Arc::new(Self {
             data: Arc::new(RwLock::new(HashMap::new())),
-            emitters: Arc::new(RwLock::new(HashMap::new())),
+            registrations: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(HashMap::new())),
+            stale: Arc::new(RwLock::new(HashSet::new())),
+            stale_threshold: AtomicU64::new(DEFAULT_STALE_THRESHOLD),
+            queue: Mutex::new(Vec::new()),
+            handler,
+            policies: Mutex::new(HashMap::new()),
+            can: RwLock::new(None),
         })
     }
 
-    pub(crate) async fn replace(&self, ffi: DeviceDatas) {
-        *self.data.write().await = ffi
-            .into_vec()
-            .into_iter()
-            .map(|data| (data.device, data))
-            .collect();
+    /// Merge a full snapshot of data from the robot, keeping the last data of
+    /// devices missing from this update so they can be detected as stale.
+    /// `timestamp` is the FPGA time of the update, in microseconds.
+    pub(crate) async fn update(&self, ffi: DeviceDatas, timestamp: u64) {
+        let mut changed = Vec::new();
+        let mut newly_stale = Vec::new();
+
+        {
+            let mut data = self.data.write().await;
+            let mut history = self.history.write().await;
+            let mut stale = self.stale.write().await;
+            let threshold = self.stale_threshold.load(Ordering::Acquire);
+
+            for device_data in ffi.into_vec() {
+                let device = device_data.device;
+
+                match data.get_mut(&device) {
+                    // keep the existing data, only noting it's still current
+                    Some(current) if current.same_data(&device_data) => {
+                        current.timestamp = device_data.timestamp;
+                    }
+                    Some(current) => {
+                        let latest = device_data.timestamp;
+                        let previous = mem::replace(current, device_data);
+
+                        if let Some(history) = history.get_mut(&device) {
+                            history.push(previous, latest);
+                        }
 
-        task::spawn(async move {
-            let ctx = DeviceContext::instance();
-            let data = ctx.data.read().await;
-            let emitters = ctx.emitters.read().await;
+                        changed.push(device);
+                    }
+                    None => {
+                        data.insert(device, device_data);
+                        changed.push(device);
+                    }
+                }
+            }
+
+            // devices are supplied every update, but timestamped with when their
+            // data was received, so one that stops reporting stays stale
+            for (device, device_data) in data.iter() {
+                if timestamp.saturating_sub(device_data.timestamp) <= threshold {
+                    stale.remove(device);
+                } else if stale.insert(*device) {
+                    newly_stale.push(Stale {
+                        device: *device,
+                        last_seen: Time::new::<microsecond>(device_data.timestamp as f64),
+                        now: Time::new::<microsecond>(timestamp as f64),
+                    });
+                }
+            }
+        }
+
+        let runtime = Runtime::current();
+        let data = Arc::clone(&self.data);
+        let registrations = Arc::clone(&self.registrations);
+
+        runtime.spawn(async move {
+            let data = data.read().await;
+            let registrations = registrations.read().await;
             let mut futures = Vec::new();
 
-            for (device, data) in data.iter() {
-                let Some(handler) = emitters.get(device) else {
+            for device in &changed {
+                let (Some(registration), Some(data)) =
+                    (registrations.get(device), data.get(device))
+                else {
                     continue;
                 };
 
-                futures.push(handler(data));
+                futures.push((registration.emit)(data));
             }
 
+            // the futures own their decoded data, so callbacks can read device
+            // data, or the next update can merge, while they run
+            drop(registrations);
+            drop(data);
+
             future::join_all(futures).await;
         });
+
+        for stale in newly_stale {
+            warn!(
+                "Device ({:?} {}) has not been updated since {:?}",
+                stale.device.kind(),
+                stale.device.id(),
+                stale.last_seen
+            );
+
+            let emitter = Arc::clone(runtime.emitter());
+            runtime.spawn(async move {
+                emitter.emit(DeviceStale::event(), Arc::new(stale)).await;
+            });
+        }
+    }
+
+    /// Record the CAN bus status supplied alongside device data
+    pub(crate) async fn update_can(&self, ffi: ferrobot_ffi::CanStatus, timestamp: u64) {
+        let status = CanStatus::new(&ffi, timestamp);
+        let previous = self.can.write().await.replace(status);
+        status.log_changes(previous.as_ref());
+
+        Emitter::current()
+            .emit(CanBusStatus::event(), Arc::new(status))
+            .await;
+    }
+
+    pub(crate) async fn can_status(&self) -> Option<CanStatus> {
+        *self.can.read().await
+    }
+
+    /// Start keeping past data of `device`, replacing any existing history if
+    /// the config changed
+    pub(crate) async fn keep_history<D: Device>(&self, device: &D, config: HistoryConfig) {
+        self.history
+            .write()
+            .await
+            .insert(device.into(), History::new(config));
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn set_stale_threshold(&self, threshold: Time) {
+        let threshold = threshold.get::<microsecond>().max(0.0) as u64;
+        self.stale_threshold.store(threshold, Ordering::Release);
+    }
+
+    pub(crate) fn set_command_policy(&self, kind: device_ffi::Type, policy: CommandPolicy) {
+        self.policies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(kind, policy);
     }
 
-    pub(crate) async fn command<D: Device>(
+    fn command_policy(&self, kind: device_ffi::Type) -> CommandPolicy {
+        self.policies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&kind)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Send `command` to `device`, returning once the robot has handled it.
+    /// Commands issued during a tick are handed to the robot as soon as the
+    /// tick waits on them. Commands issued outside a tick, e.g. from a spawned
+    /// task, wait for the next one, so are handled up to a robot loop (20ms)
+    /// later.
+    ///
+    /// Errors are located at the device method that called this, rather than
+    /// deep in the command queue.
+    #[track_caller]
+    pub(crate) fn command<D: Device>(
         &self,
         device: &D,
         command: D::CommandFFI,
-    ) -> Result<
-        Result<
-            *const <D::CommandFFI as device::Command>::Ok,
-            *const <D::CommandFFI as device::Command>::Error,
-        >,
-        Error,
-    > {
-        if !self.device_exists(device).await {
-            return Err(Error::DeviceNotFound(D::TYPE, device.id()));
+    ) -> impl Future<Output = Result<<D::CommandFFI as device::Command>::Ok, Error>> + Send {
+        let location = Location::caller();
+        let command = device_ffi::Command::new(device, command);
+
+        async move {
+            if !self.device_exists(device).await {
+                return Err(Error::new(ErrorKind::NotFound, "Device not registered")
+                    .with_device(device.into())
+                    .at(location));
+            }
+
+            self.submit(command, location).await
         }
+    }
 
-        let command = device_ffi::Command::new(device, command);
-        let res = unsafe { handle_command(&command) };
+    /// Queue a command to be handled on the next flush, along with every other
+    /// command issued since the last one. Transient errors are retried,
+    /// and each attempt is timed out, according to the device type's policy.
+    ///
+    /// A timeout only stops this wait. It can't interrupt the robot while it
+    /// handles a command, so a command that timed out may still be handled,
+    /// but it will not be retried.
+    async fn submit<T: Decode>(
+        &self,
+        mut command: device_ffi::Command,
+        location: &'static Location<'static>,
+    ) -> Result<T, Error> {
+        let device = command.device;
+        let policy = self.command_policy(device.kind());
+        let mut attempt = 0;
+
+        loop {
+            let (sender, receiver) = oneshot::channel();
+
+            self.queue
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((command, sender));
+
+            let (returned, response) = executor::timeout(policy.timeout, receiver)
+                .await
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::Timeout,
+                        format!("Command was not handled within {:?}", policy.timeout),
+                    )
+                    .with_device(device)
+                    .at(location)
+                })?
+                .map_err(|_| {
+                    Error::new(
+                        ErrorKind::CommandDropped,
+                        "Command was dropped before it was handled",
+                    )
+                    .with_device(device)
+                    .at(location)
+                })?;
+
+            let Some(response) = response else {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    "No robot is running to handle commands",
+                )
+                .with_device(device)
+                .at(location));
+            };
+
+            let err = match response.decode() {
+                Ok(ok) => return Ok(ok),
+                Err(err) => Error::from(err).with_device(device).at(location),
+            };
+
+            if !err.kind().is_transient() || attempt >= policy.retries {
+                return Err(err);
+            }
+
+            attempt += 1;
+            warn!("{err}, retrying ({attempt}/{})", policy.retries);
 
-        if res.ok {
-            return Ok(Ok(res.data.cast()));
+            executor::sleep(policy.retry_delay).await;
+            command = returned;
         }
+    }
 
-        Ok(Err(res.data.cast()))
+    /// Whether any commands are waiting to be handed to the robot
+    pub(crate) fn has_queued(&self) -> bool {
+        !self
+            .queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    /// Hand every queued command to the robot in one batch, and wake the
+    /// tasks waiting on them, so setpoints are applied together. Returns
+    /// whether any commands were queued.
+    pub(crate) fn flush(&self) -> bool {
+        let pending = mem::take(&mut *self.queue.lock().unwrap_or_else(PoisonError::into_inner));
+
+        if pending.is_empty() {
+            return false;
+        }
+
+        let (commands, senders): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+        let started = Instant::now();
+        let responses = (self.handler)(&commands);
+        let elapsed = started.elapsed();
+        let loop_period = Runtime::current().scheduler().loop_period();
+
+        // the robot's calls can't be interrupted, so a slow one holds up the
+        // tick however short the commands' timeouts are
+        if elapsed > loop_period {
+            warn!(
+                "Robot took {elapsed:?} to handle {} commands, longer than the loop period of \
+                 {loop_period:?}",
+                commands.len()
+            );
+        }
+
+        let responses = responses.map_or_else(
+            || commands.iter().map(|_| None).collect(),
+            |responses| responses.into_iter().map(Some).collect::<Vec<_>>(),
+        );
+
+        for ((sender, command), response) in senders.into_iter().zip(commands).zip(responses) {
+            // the waiting task may have timed out or been cancelled
+            let _ = sender.send((command, response));
+        }
+
+        true
     }
 
     pub(crate) async fn add_device<D: Device>(&self, device: &Arc<D>) -> Result<(), Error> {
-        let device = Arc::clone(device);
-        let device_ffi = (&*device).into();
+        let device_ffi = (&**device).into();
+
+        let dropped = {
+            let mut registrations = self.registrations.write().await;
 
-        if self.device_exists(&*device).await {
-            return Err(Error::DeviceAlreadyRegistered(D::TYPE, device.id()));
+            match registrations.get(&device_ffi) {
+                Some(registration) if registration.owner.strong_count() > 0 => {
+                    return Err(
+                        Error::new(ErrorKind::AlreadyExists, "Device already registered")
+                            .with_device(device_ffi),
+                    );
+                }
+                // the previous device was dropped, but hasn't been released yet
+                Some(_) => registrations.remove(&device_ffi),
+                None => None,
+            }
+        };
+
+        if let Some(registration) = dropped
+            && let Err(err) = self.release_registration(device_ffi, registration).await
+        {
+            warn!("{err}");
         }
 
+        let weak = Arc::downgrade(device);
+        let emitter = Emitter::current();
         let callback = Arc::new(move |data: &device_ffi::Data| {
-            let device = Arc::clone(&device);
+            let Some(device) = weak.upgrade() else {
+                return Box::pin(future::ready(())) as BoxFuture<'static, ()>;
+            };
+
             let ptr = data.data.cast::<D::DataFFI>();
             let deref = unsafe { &*ptr };
             let data = Arc::new(deref.into());
-            Box::pin(Emitter::instance().emit_device(device, data)) as BoxFuture<'static, ()>
+            let emitter = Arc::clone(&emitter);
+            Box::pin(async move { emitter.emit_device(device, data).await })
+                as BoxFuture<'static, ()>
         });
 
-        let mut devices = self.emitters.write().await;
-        devices.insert(device_ffi, callback);
+        let mut registrations = self.registrations.write().await;
+        if registrations.contains_key(&device_ffi) {
+            return Err(
+                Error::new(ErrorKind::AlreadyExists, "Device already registered")
+                    .with_device(device_ffi),
+            );
+        }
+
+        registrations.insert(
+            device_ffi,
+            Registration {
+                owner: Arc::downgrade(device) as Weak<dyn Any + Send + Sync>,
+                emit: callback,
+                destroy: device
+                    .destroy_command()
+                    .map(|command| device_ffi::Command::new(&**device, command)),
+            },
+        );
 
         Ok(())
     }
 
+    /// Release `device` on the robot and forget its data, if it is still the
+    /// registered device. `owner` is the address of the registered device,
+    /// and `dropped` whether it has been dropped rather than closed.
+    pub(crate) async fn release(
+        &self,
+        device: device_ffi::Device,
+        owner: usize,
+        dropped: bool,
+    ) -> Result<(), Error> {
+        let registration = {
+            let mut registrations = self.registrations.write().await;
+
+            // a device created after this one was dropped may have been
+            // allocated at the same address, but it's still alive
+            match registrations.get(&device) {
+                Some(registration)
+                    if registration.owner.as_ptr().cast::<()>().addr() == owner
+                        && (!dropped || registration.owner.strong_count() == 0) =>
+                {
+                    registrations.remove(&device)
+                }
+                _ => None,
+            }
+        };
+
+        let Some(registration) = registration else {
+            return Err(
+                Error::new(ErrorKind::NotFound, "Device not registered").with_device(device)
+            );
+        };
+
+        self.release_registration(device, registration).await
+    }
+
+    async fn release_registration(
+        &self,
+        device: device_ffi::Device,
+        registration: Registration,
+    ) -> Result<(), Error> {
+        self.data.write().await.remove(&device);
+        self.history.write().await.remove(&device);
+        self.stale.write().await.remove(&device);
+
+        let Some(command) = registration.destroy else {
+            return Ok(());
+        };
+
+        self.submit(command, Location::caller()).await
+    }
+
     pub(crate) async fn device_exists<D: Device>(&self, device: &D) -> bool {
-        self.emitters.read().await.contains_key(&device.into())
+        self.registrations.read().await.contains_key(&device.into())
     }
 
-    pub(crate) async fn data<D: Device + 'static>(&self, device: &D) -> Option<D::Data> {
+    pub(crate) async fn data<D: Device + 'static>(
+        &self,
+        device: &D,
+    ) -> Option<Timestamped<D::Data>> {
         let device = device.into();
 
         let map = self.data.read().await;
@@ -125,9 +534,221 @@ impl DeviceContext {
         let ptr = data.data.cast::<D::DataFFI>();
         let deref = unsafe { &*ptr };
 
-        Some(deref.into())
+        Some(Timestamped {
+            data: deref.into(),
+            timestamp: Time::new::<microsecond>(data.timestamp as f64),
+        })
+    }
+
+    /// The data of `device` at `timestamp`, interpolated between the readings
+    /// either side of it. Clamped to the oldest and latest readings, which
+    /// keep their own timestamps.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) async fn data_at<D: Device + 'static>(
+        &self,
+        device: &D,
+        timestamp: Time,
+    ) -> Option<Timestamped<D::Data>>
+    where
+        D::Data: Interpolate,
+    {
+        let device = device.into();
+        let target = timestamp.get::<microsecond>().max(0.0) as u64;
+
+        let map = self.data.read().await;
+        let history = self.history.read().await;
+        let latest = map.get(&device)?;
+        let decode = |data: &device_ffi::Data| -> D::Data {
+            let ptr = data.data.cast::<D::DataFFI>();
+            unsafe { &*ptr }.into()
+        };
+
+        let mut before = None;
+        let mut after = None;
+        for entry in history
+            .get(&device)
+            .into_iter()
+            .flat_map(History::iter)
+            .chain([latest])
+        {
+            if entry.timestamp <= target {
+                before = Some(entry);
+            } else {
+                after = Some(entry);
+                break;
+            }
+        }
+
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                let t = (target - before.timestamp) as f64
+                    / (after.timestamp - before.timestamp) as f64;
+
+                Some(Timestamped {
+                    data: decode(before).interpolate(&decode(after), t),
+                    timestamp,
+                })
+            }
+            // clamped, so the reading keeps its own timestamp
+            (Some(only), None) | (None, Some(only)) => Some(Timestamped {
+                data: decode(only),
+                timestamp: Time::new::<microsecond>(only.timestamp as f64),
+            }),
+            (None, None) => None,
+        }
     }
 }
 
 unsafe impl Send for DeviceContext {}
 unsafe impl Sync for DeviceContext {}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, time::Duration};
+
+    use super::*;
+    use crate::device::{
+        HistoryConfig,
+        io::{DigitalOutput, prelude::io_ffi},
+        pwm::{
+            Controller, MotorData, PwmMotorConfig,
+            prelude::{PwmMotor, pwm_ffi},
+        },
+    };
+
+    /// An update with a digital output whose data was received at `received`
+    fn update(received: u64) -> DeviceDatas {
+        let data = Box::new(io_ffi::DigitalData { value: false });
+
+        DeviceDatas::from_vec(vec![device_ffi::Data {
+            device: device_ffi::Device::new(
+                device_ffi::Type::DigitalOutput,
+                device_ffi::Bus::Dio,
+                0,
+            ),
+            data: Box::into_raw(data).cast(),
+            timestamp: received,
+        }])
+    }
+
+    #[test]
+    fn device_goes_stale_once_until_its_data_is_received() {
+        let runtime = Runtime::with_handler(Arc::new(|_| None));
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&emitted);
+
+        runtime.block_on(async {
+            let ctx = DeviceContext::current();
+
+            Emitter::current()
+                .register(
+                    &DeviceStale::event(),
+                    Arc::new(move |_| {
+                        count.fetch_add(1, Ordering::AcqRel);
+                        Box::pin(async {})
+                    }),
+                )
+                .await;
+
+            // still supplied, but no new data after the first update
+            for timestamp in [0, 200_000, 400_000] {
+                ctx.update(update(0), timestamp).await;
+            }
+
+            assert_eq!(ctx.stale.read().await.len(), 1);
+
+            ctx.update(update(600_000), 600_000).await;
+            assert!(ctx.stale.read().await.is_empty());
+
+            // stale events are emitted from their own tasks
+            runtime.sleep(Duration::from_millis(50)).await;
+        });
+
+        assert_eq!(emitted.load(Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn data_at_keeps_the_timestamp_of_a_clamped_reading() {
+        let runtime = Runtime::with_handler(Arc::new(|commands| {
+            Some(
+                commands
+                    .iter()
+                    .map(|_| ferrobot_ffi::Response::ok())
+                    .collect(),
+            )
+        }));
+        let config = PwmMotorConfig::builder()
+            .controller(Controller::Spark)
+            .build();
+
+        runtime.block_on(async {
+            let (motor, ()) = futures::join!(PwmMotor::new(0, config), runtime.tick(0));
+            let motor = motor.unwrap();
+            let ctx = DeviceContext::current();
+            motor.keep_history(HistoryConfig::default()).await;
+
+            for (speed, timestamp) in [(0.0, 100_000), (1.0, 200_000)] {
+                let data = Box::new(pwm_ffi::MotorData { speed });
+                let update = DeviceDatas::from_vec(vec![device_ffi::Data {
+                    device: (&*motor).into(),
+                    data: Box::into_raw(data).cast(),
+                    timestamp,
+                }]);
+
+                ctx.update(update, timestamp).await;
+            }
+
+            let at = |micros: f64| motor.data_at(Time::new::<microsecond>(micros));
+            let between = at(150_000.0).await.unwrap();
+            let before = at(50_000.0).await.unwrap();
+            let after = at(250_000.0).await.unwrap();
+
+            assert_eq!(between.data, MotorData { speed: 0.5 });
+            assert_eq!(between.timestamp, Time::new::<microsecond>(150_000.0));
+            assert_eq!(before.data, MotorData { speed: 0.0 });
+            assert_eq!(before.timestamp, Time::new::<microsecond>(100_000.0));
+            assert_eq!(after.data, MotorData { speed: 1.0 });
+            assert_eq!(after.timestamp, Time::new::<microsecond>(200_000.0));
+        });
+    }
+
+    #[test]
+    fn release_of_a_dropped_device_skips_a_new_one_at_the_same_address() {
+        let runtime = Runtime::with_handler(Arc::new(|commands| {
+            Some(
+                commands
+                    .iter()
+                    .map(|_| ferrobot_ffi::Response::ok())
+                    .collect(),
+            )
+        }));
+
+        runtime.block_on(async {
+            let (output, ()) = futures::join!(DigitalOutput::new(0), runtime.tick(0));
+            let output = output.unwrap();
+            let ctx = DeviceContext::current();
+            let owner = Arc::as_ptr(&output).addr();
+
+            // as if a device dropped at the same address was released late
+            let err = ctx
+                .release((&*output).into(), owner, true)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.kind(), ErrorKind::NotFound);
+            assert!(ctx.device_exists(&*output).await);
+        });
+    }
+
+    #[test]
+    fn command_errors_are_located_at_the_device_method() {
+        let runtime = Runtime::with_handler(Arc::new(|_| None));
+
+        let (output, ()) =
+            runtime.block_on(async { futures::join!(DigitalOutput::new(0), runtime.tick(0)) });
+        let err = output.unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.location().file().ends_with("digital.rs"), "{err}");
+    }
+}
@@ -1,4 +1,9 @@
-use std::{ffi::c_void, ptr};
+use std::{
+    ffi::{CStr, c_char, c_void},
+    fmt, ptr,
+};
+
+use interoptopus::ffi::CStrPtr;
 
 use super::prelude::*;
 
@@ -6,31 +11,110 @@ use super::prelude::*;
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Type {
     SparkMax,
+    SparkFlex,
+    TalonFX,
+    CanCoder,
+    Pigeon2,
+    PwmMotor,
+    Servo,
+    DigitalInput,
+    DigitalOutput,
+    AnalogInput,
+    DutyCycleEncoder,
+    Solenoid,
+    DoubleSolenoid,
+    Compressor,
+    PowerDistribution,
+    AddressableLed,
+    Camera,
     NavX,
     XboxController,
 }
 
+/// The bus a device is addressed on. Ids are only unique within a bus.
+#[ffi_type(namespace = "ffi::device")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Can,
+    Pwm,
+    Dio,
+    Analog,
+    Network,
+}
+
+/// Identifies a registered device
 #[ffi_type(namespace = "ffi::device")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub(crate) struct Device {
+pub struct Device {
     kind: Type,
+    bus: Bus,
     id: u8,
+    channel: u8,
+}
+
+impl Device {
+    #[cfg(test)]
+    pub(crate) fn new(kind: Type, bus: Bus, id: u8) -> Self {
+        Self {
+            kind,
+            bus,
+            id,
+            channel: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> Type {
+        self.kind
+    }
+
+    #[must_use]
+    pub fn bus(&self) -> Bus {
+        self.bus
+    }
+
+    #[must_use]
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.kind, self.id)?;
+
+        if self.channel != 0 {
+            write!(f, " channel {}", self.channel)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<D: super::Device> From<&D> for Device {
     fn from(device: &D) -> Self {
         Self {
             kind: D::TYPE,
+            bus: D::BUS,
             id: device.id(),
+            channel: device.channel(),
         }
     }
 }
 
+#[allow(clippy::struct_field_names)]
 #[ffi_type(namespace = "ffi::device")]
 #[derive(Debug)]
 pub(crate) struct Data {
     pub(crate) device: Device,
     pub(crate) data: *const c_void,
+    /// FPGA time the data was read, in microseconds
+    pub(crate) timestamp: u64,
 }
 
 impl Data {
@@ -41,6 +125,58 @@ impl Data {
         Self {
             device: self.device,
             data,
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// Whether `other` holds the same data for the same device, ignoring when
+    /// it was read
+    pub(crate) fn same_data(&self, other: &Self) -> bool {
+        fn eq<T: PartialEq>(a: &Data, b: &Data) -> bool {
+            unsafe { *a.data.cast::<T>() == *b.data.cast::<T>() }
+        }
+
+        if self.device != other.device {
+            return false;
+        }
+
+        match self.device.kind {
+            Type::SparkMax | Type::SparkFlex => {
+                eq::<<spark::SparkMax as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::TalonFX => eq::<<talonfx::TalonFX as super::DeviceFFI>::DataFFI>(self, other),
+            Type::CanCoder => eq::<<cancoder::CanCoder as super::DeviceFFI>::DataFFI>(self, other),
+            Type::Pigeon2 => eq::<<pigeon::Pigeon2 as super::DeviceFFI>::DataFFI>(self, other),
+            Type::PwmMotor => eq::<<pwm::PwmMotor as super::DeviceFFI>::DataFFI>(self, other),
+            Type::Servo => eq::<<pwm::Servo as super::DeviceFFI>::DataFFI>(self, other),
+            Type::DigitalInput => {
+                eq::<<io::DigitalInput as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::DigitalOutput => {
+                eq::<<io::DigitalOutput as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::AnalogInput => eq::<<io::AnalogInput as super::DeviceFFI>::DataFFI>(self, other),
+            Type::DutyCycleEncoder => {
+                eq::<<io::DutyCycleEncoder as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::Solenoid => {
+                eq::<<pneumatics::Solenoid as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::DoubleSolenoid => {
+                eq::<<pneumatics::DoubleSolenoid as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::Compressor => {
+                eq::<<pneumatics::Compressor as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::PowerDistribution => {
+                eq::<<power::PowerDistribution as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::AddressableLed => {
+                eq::<<led::AddressableLed as super::DeviceFFI>::DataFFI>(self, other)
+            }
+            Type::Camera => eq::<<vision::Camera as super::DeviceFFI>::DataFFI>(self, other),
+            // not supplied yet, so never compared
+            Type::NavX | Type::XboxController => false,
         }
     }
 }
@@ -49,9 +185,54 @@ impl Drop for Data {
     fn drop(&mut self) {
         unsafe {
             match self.device.kind {
-                Type::SparkMax => drop(Box::from_raw(
+                Type::SparkMax | Type::SparkFlex => drop(Box::from_raw(
                     self.data as *mut <spark::SparkMax as super::DeviceFFI>::DataFFI,
                 )),
+                Type::TalonFX => drop(Box::from_raw(
+                    self.data as *mut <talonfx::TalonFX as super::DeviceFFI>::DataFFI,
+                )),
+                Type::CanCoder => drop(Box::from_raw(
+                    self.data as *mut <cancoder::CanCoder as super::DeviceFFI>::DataFFI,
+                )),
+                Type::Pigeon2 => drop(Box::from_raw(
+                    self.data as *mut <pigeon::Pigeon2 as super::DeviceFFI>::DataFFI,
+                )),
+                Type::PwmMotor => drop(Box::from_raw(
+                    self.data as *mut <pwm::PwmMotor as super::DeviceFFI>::DataFFI,
+                )),
+                Type::Servo => drop(Box::from_raw(
+                    self.data as *mut <pwm::Servo as super::DeviceFFI>::DataFFI,
+                )),
+                Type::DigitalInput => drop(Box::from_raw(
+                    self.data as *mut <io::DigitalInput as super::DeviceFFI>::DataFFI,
+                )),
+                Type::DigitalOutput => drop(Box::from_raw(
+                    self.data as *mut <io::DigitalOutput as super::DeviceFFI>::DataFFI,
+                )),
+                Type::AnalogInput => drop(Box::from_raw(
+                    self.data as *mut <io::AnalogInput as super::DeviceFFI>::DataFFI,
+                )),
+                Type::DutyCycleEncoder => drop(Box::from_raw(
+                    self.data as *mut <io::DutyCycleEncoder as super::DeviceFFI>::DataFFI,
+                )),
+                Type::Solenoid => drop(Box::from_raw(
+                    self.data as *mut <pneumatics::Solenoid as super::DeviceFFI>::DataFFI,
+                )),
+                Type::DoubleSolenoid => drop(Box::from_raw(
+                    self.data as *mut <pneumatics::DoubleSolenoid as super::DeviceFFI>::DataFFI,
+                )),
+                Type::Compressor => drop(Box::from_raw(
+                    self.data as *mut <pneumatics::Compressor as super::DeviceFFI>::DataFFI,
+                )),
+                Type::PowerDistribution => drop(Box::from_raw(
+                    self.data as *mut <power::PowerDistribution as super::DeviceFFI>::DataFFI,
+                )),
+                Type::AddressableLed => drop(Box::from_raw(
+                    self.data as *mut <led::AddressableLed as super::DeviceFFI>::DataFFI,
+                )),
+                Type::Camera => drop(Box::from_raw(
+                    self.data as *mut <vision::Camera as super::DeviceFFI>::DataFFI,
+                )),
                 Type::NavX => unimplemented!(), // TODO: NavX
                 Type::XboxController => unimplemented!(), // TODO: XboxController
             }
@@ -83,9 +264,39 @@ impl Drop for Command {
     fn drop(&mut self) {
         unsafe {
             match self.device.kind {
-                Type::SparkMax => drop(Box::from_raw(
+                Type::SparkMax | Type::SparkFlex => drop(Box::from_raw(
                     self.command as *mut <spark::SparkMax as super::DeviceFFI>::CommandFFI,
                 )),
+                Type::TalonFX => drop(Box::from_raw(
+                    self.command as *mut <talonfx::TalonFX as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::CanCoder => drop(Box::from_raw(
+                    self.command as *mut <cancoder::CanCoder as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::Pigeon2 => drop(Box::from_raw(
+                    self.command as *mut <pigeon::Pigeon2 as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::PwmMotor | Type::Servo => drop(Box::from_raw(
+                    self.command as *mut <pwm::PwmMotor as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::DigitalInput
+                | Type::DigitalOutput
+                | Type::AnalogInput
+                | Type::DutyCycleEncoder => drop(Box::from_raw(
+                    self.command as *mut <io::DigitalInput as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::Solenoid | Type::DoubleSolenoid | Type::Compressor => drop(Box::from_raw(
+                    self.command as *mut <pneumatics::Solenoid as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::PowerDistribution => drop(Box::from_raw(
+                    self.command as *mut <power::PowerDistribution as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::AddressableLed => drop(Box::from_raw(
+                    self.command as *mut <led::AddressableLed as super::DeviceFFI>::CommandFFI,
+                )),
+                Type::Camera => drop(Box::from_raw(
+                    self.command as *mut <vision::Camera as super::DeviceFFI>::CommandFFI,
+                )),
                 Type::NavX => unimplemented!(), // TODO: NavX
                 Type::XboxController => unimplemented!(), // TODO: XboxController
             }
@@ -97,10 +308,83 @@ impl Drop for Command {
 unsafe impl Send for Command {}
 unsafe impl Sync for Command {}
 
+#[allow(dead_code)]
+#[ffi_type(namespace = "ffi::device")]
+#[derive(Clone, Copy, Debug, PartialEq, derive_more::Display)]
+pub enum ErrorType {
+    #[display("Tried to create existing device")]
+    DeviceExists,
+    #[display("Device does not exist")]
+    DeviceNotFound,
+    #[display("Invalid device configuration")]
+    BadConfig,
+    #[display("Invalid device command")]
+    BadCommand,
+    #[display("Command not supported by device")]
+    UnsupportedCommand,
+    #[display("Timed out")]
+    Timeout,
+    #[display("CAN fault")]
+    CanFault,
+    /// `code` holds the HAL or vendor status code
+    #[display("HAL error")]
+    Hal,
+}
+
+#[allow(dead_code)]
+#[ffi_type(namespace = "ffi::device")]
+#[derive(Debug, thiserror::Error)]
+#[error("{kind}: {}", message.as_str().unwrap_or("Unknown error"))]
+pub struct Error {
+    pub(crate) kind: ErrorType,
+    /// Heap-allocated string of which Rust has the responsibility of freeing
+    pub(crate) message: CStrPtr<'static>,
+    /// HAL or vendor status code, or 0
+    pub(crate) code: i32,
+    /// `REVLibError` code, or 0 (`kOk`) if the error didn't come from `REVLib`
+    pub(crate) rev_status: i32,
+}
+
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        let c_str = self.message.as_c_str().unwrap();
+        let message = unsafe {
+            let ptr = libc::malloc(c_str.to_bytes().len() + 1).cast::<c_char>();
+            libc::strcpy(ptr, c_str.as_ptr());
+            CStr::from_ptr(ptr)
+        };
+
+        Self {
+            message: CStrPtr::from_cstr(message),
+            kind: self.kind,
+            code: self.code,
+            rev_status: self.rev_status,
+        }
+    }
+}
+
+impl Drop for Error {
+    fn drop(&mut self) {
+        let Some(c_str) = self.message.as_c_str() else {
+            return;
+        };
+
+        unsafe {
+            let ptr = c_str.as_ptr();
+            if !ptr.is_null() {
+                libc::free(ptr as *mut libc::c_void);
+            }
+        }
+    }
+}
+
 #[cfg(feature = "build")]
 pub(super) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
     builder
         .register(extra_type!(Type))
+        .register(extra_type!(Bus))
         .register(extra_type!(Data))
         .register(extra_type!(Command))
+        .register(extra_type!(ErrorType))
+        .register(extra_type!(Error))
 }
@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use typed_builder::TypedBuilder;
+use uom::si::{Dimension, Quantity, Units, angle::revolution, time::microsecond};
+
+use super::prelude::*;
+
+/// A value that can be estimated between two readings
+pub trait Interpolate {
+    /// Estimate the value a fraction `t` of the way from `self` to `other`,
+    /// where `t` is between 0.0 and 1.0
+    #[must_use]
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for bool {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        *nearest(self, other, t)
+    }
+}
+
+impl<D, U> Interpolate for Quantity<D, U, f64>
+where
+    D: Dimension + ?Sized,
+    U: Units<f64> + ?Sized,
+{
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            dimension: self.dimension,
+            units: self.units,
+            value: self.value.interpolate(&other.value, t),
+        }
+    }
+}
+
+impl<T: Interpolate + Clone> Interpolate for Option<T> {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.interpolate(b, t)),
+            _ => nearest(self, other, t).clone(),
+        }
+    }
+}
+
+impl<T: Interpolate, const N: usize> Interpolate for [T; N] {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        std::array::from_fn(|i| self[i].interpolate(&other[i], t))
+    }
+}
+
+/// The closer of two readings, for values that can't be interpolated
+pub fn nearest<'a, T>(a: &'a T, b: &'a T, t: f64) -> &'a T {
+    if t < 0.5 { a } else { b }
+}
+
+/// Interpolate an angle that wraps every rotation, e.g. an absolute encoder,
+/// along the shortest path. The result is not wrapped, so it may fall just
+/// outside the sensor's range when interpolating across the discontinuity.
+#[must_use]
+pub fn interpolate_wrapped(a: Angle, b: Angle, t: f64) -> Angle {
+    let delta = (b - a).get::<revolution>();
+    let delta = delta - delta.round();
+
+    a + Angle::new::<revolution>(delta * t)
+}
+
+/// How much history to keep for a device. Readings are dropped once either
+/// limit is reached.
+#[derive(Clone, Copy, Debug, TypedBuilder)]
+pub struct HistoryConfig {
+    /// The maximum number of past readings
+    #[builder(default = 50)]
+    pub length: usize,
+
+    /// The maximum age of a reading, relative to the latest
+    #[builder(default = Time::new::<second>(1.0))]
+    pub duration: Time,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Past readings of a device, oldest first
+#[derive(Debug)]
+pub(crate) struct History {
+    config: HistoryConfig,
+    entries: VecDeque<device_ffi::Data>,
+}
+
+impl History {
+    pub(crate) fn new(config: HistoryConfig) -> Self {
+        Self {
+            config,
+            entries: VecDeque::with_capacity(config.length),
+        }
+    }
+
+    /// Add a reading that has been replaced by one taken at `latest`, and
+    /// drop readings over the configured limits
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn push(&mut self, data: device_ffi::Data, latest: u64) {
+        let duration = self.config.duration.get::<microsecond>().max(0.0) as u64;
+
+        self.entries.push_back(data);
+
+        while self.entries.len() > self.config.length
+            || self
+                .entries
+                .front()
+                .is_some_and(|oldest| latest.saturating_sub(oldest.timestamp) > duration)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &device_ffi::Data> {
+        self.entries.iter()
+    }
+}
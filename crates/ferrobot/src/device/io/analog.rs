@@ -0,0 +1,69 @@
+use super::{AnalogInputConfig, Error, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnalogData {
+    /// The most recent voltage sample
+    pub voltage: ElectricPotential,
+    /// The voltage averaged over the configured number of samples
+    pub average_voltage: ElectricPotential,
+}
+
+impl<'a> From<&'a io_ffi::AnalogData> for AnalogData {
+    fn from(value: &'a io_ffi::AnalogData) -> Self {
+        Self {
+            voltage: ElectricPotential::new::<volt>(value.voltage),
+            average_voltage: ElectricPotential::new::<volt>(value.average_voltage),
+        }
+    }
+}
+
+impl Interpolate for AnalogData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            voltage: self.voltage.interpolate(&other.voltage, t),
+            average_voltage: self.average_voltage.interpolate(&other.average_voltage, t),
+        }
+    }
+}
+
+/// An analog input on a roboRIO analog channel, e.g. a pressure sensor or
+/// potentiometer
+#[derive(Debug)]
+pub struct AnalogInput {
+    channel: u8,
+}
+
+impl AnalogInput {
+    pub async fn new(channel: u8, config: AnalogInputConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { channel });
+        let command = io_ffi::Command::create_analog_input(config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+}
+
+impl device::DeviceFFI for AnalogInput {
+    type CommandFFI = io_ffi::Command;
+    type DataFFI = io_ffi::AnalogData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Analog;
+    const TYPE: device_ffi::Type = device_ffi::Type::AnalogInput;
+}
+
+impl device::Device for AnalogInput {
+    type Data = AnalogData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for AnalogInput {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
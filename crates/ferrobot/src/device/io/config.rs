@@ -0,0 +1,46 @@
+use typed_builder::TypedBuilder;
+use uom::si::angle::revolution;
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::io::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TypedBuilder)]
+pub struct AnalogInputConfig {
+    /// Average 2^bits samples together for the averaged voltage
+    #[builder(default = 7)]
+    pub average_bits: u8,
+
+    /// Oversample 2^bits samples together, increasing resolution
+    #[builder(default = 0)]
+    pub oversample_bits: u8,
+}
+
+#[ffi_type(namespace = "ffi::device::io::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct DutyCycleEncoderConfig {
+    /// The reading at the mechanism's zero position, in rotations
+    #[builder(default = 0.0, setter(transform = |offset: Angle| offset.get::<revolution>()))]
+    pub offset: f64,
+
+    #[builder(default = false)]
+    pub inverted: bool,
+}
+
+impl Default for AnalogInputConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Default for DutyCycleEncoderConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(AnalogInputConfig))
+        .register(extra_type!(DutyCycleEncoderConfig))
+}
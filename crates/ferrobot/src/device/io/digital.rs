@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use super::{Error, prelude::*};
+use crate::control::event::{Emitter, Event, EventExt};
+
+// the last value seen by a digital input, before any data has arrived
+const UNKNOWN: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DigitalData {
+    pub value: bool,
+}
+
+impl<'a> From<&'a io_ffi::DigitalData> for DigitalData {
+    fn from(value: &'a io_ffi::DigitalData) -> Self {
+        Self { value: value.value }
+    }
+}
+
+impl Interpolate for DigitalData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            value: self.value.interpolate(&other.value, t),
+        }
+    }
+}
+
+/// Emitted when a digital input changes from low to high
+#[derive(Debug)]
+pub struct Rising {
+    channel: u8,
+}
+
+impl Event for Rising {
+    type Data = DigitalData;
+}
+
+/// Emitted when a digital input changes from high to low
+#[derive(Debug)]
+pub struct Falling {
+    channel: u8,
+}
+
+impl Event for Falling {
+    type Data = DigitalData;
+}
+
+/// A digital input on a roboRIO DIO channel, e.g. a limit switch or beam break
+#[derive(Debug)]
+pub struct DigitalInput {
+    channel: u8,
+    rising: Arc<Rising>,
+    falling: Arc<Falling>,
+}
+
+impl DigitalInput {
+    pub async fn new(channel: u8) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            channel,
+            rising: Arc::new(Rising { channel }),
+            falling: Arc::new(Falling { channel }),
+        });
+        let command = io_ffi::Command::create_digital_input();
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+        this.watch_edges().await;
+
+        Ok(this)
+    }
+
+    pub async fn get(&self) -> Option<bool> {
+        self.data().await.map(|data| data.value)
+    }
+
+    /// An event emitted each time the input goes from low to high
+    #[must_use]
+    pub fn rising(&self) -> Arc<Rising> {
+        Arc::clone(&self.rising)
+    }
+
+    /// An event emitted each time the input goes from high to low
+    #[must_use]
+    pub fn falling(&self) -> Arc<Falling> {
+        Arc::clone(&self.falling)
+    }
+
+    async fn watch_edges(self: &Arc<Self>) {
+        let rising = self.rising();
+        let falling = self.falling();
+        let last = Arc::new(AtomicU8::new(UNKNOWN));
+
+        self.register(move |data: Arc<DigitalData>| {
+            let rising = Arc::clone(&rising);
+            let falling = Arc::clone(&falling);
+            let last = last.swap(u8::from(data.value), Ordering::AcqRel);
+
+            async move {
+                match (last, data.value) {
+                    (0, true) => {
+                        trace!("Digital input {} rising edge", rising.channel);
+                        Emitter::current().emit(rising, data).await;
+                    }
+                    (1, false) => {
+                        trace!("Digital input {} falling edge", falling.channel);
+                        Emitter::current().emit(falling, data).await;
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .await;
+    }
+}
+
+impl device::DeviceFFI for DigitalInput {
+    type CommandFFI = io_ffi::Command;
+    type DataFFI = io_ffi::DigitalData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Dio;
+    const TYPE: device_ffi::Type = device_ffi::Type::DigitalInput;
+}
+
+impl device::Device for DigitalInput {
+    type Data = DigitalData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for DigitalInput {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+/// A digital output on a roboRIO DIO channel
+#[derive(Debug)]
+pub struct DigitalOutput {
+    channel: u8,
+}
+
+impl DigitalOutput {
+    pub async fn new(channel: u8) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { channel });
+        let command = io_ffi::Command::create_digital_output();
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set(&self, value: bool) -> Result<(), Error> {
+        debug!("Setting digital output {} to {}", self.channel, value);
+
+        let ctx = DeviceContext::current();
+        let command = io_ffi::Command::set(value);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for DigitalOutput {
+    type CommandFFI = io_ffi::Command;
+    type DataFFI = io_ffi::DigitalData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Dio;
+    const TYPE: device_ffi::Type = device_ffi::Type::DigitalOutput;
+}
+
+impl device::Device for DigitalOutput {
+    type Data = DigitalData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for DigitalOutput {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
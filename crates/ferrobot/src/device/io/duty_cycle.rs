@@ -0,0 +1,70 @@
+use uom::si::angle::revolution;
+
+use super::{DutyCycleEncoderConfig, Error, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DutyCycleData {
+    pub connected: bool,
+    /// Absolute position of the encoder, from zero to one rotation
+    pub position: Angle,
+}
+
+impl<'a> From<&'a io_ffi::DutyCycleData> for DutyCycleData {
+    fn from(value: &'a io_ffi::DutyCycleData) -> Self {
+        Self {
+            connected: value.connected,
+            position: Angle::new::<revolution>(value.position),
+        }
+    }
+}
+
+impl Interpolate for DutyCycleData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            connected: self.connected.interpolate(&other.connected, t),
+            position: device::interpolate_wrapped(self.position, other.position, t),
+        }
+    }
+}
+
+/// An absolute encoder on a roboRIO DIO channel, e.g. a REV Through Bore
+/// Encoder
+#[derive(Debug)]
+pub struct DutyCycleEncoder {
+    channel: u8,
+}
+
+impl DutyCycleEncoder {
+    pub async fn new(channel: u8, config: DutyCycleEncoderConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { channel });
+        let command = io_ffi::Command::create_duty_cycle_encoder(config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+}
+
+impl device::DeviceFFI for DutyCycleEncoder {
+    type CommandFFI = io_ffi::Command;
+    type DataFFI = io_ffi::DutyCycleData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Dio;
+    const TYPE: device_ffi::Type = device_ffi::Type::DutyCycleEncoder;
+}
+
+impl device::Device for DutyCycleEncoder {
+    type Data = DutyCycleData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for DutyCycleEncoder {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
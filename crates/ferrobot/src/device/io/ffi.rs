@@ -0,0 +1,120 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::io")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    Set,
+    CreateDigitalInput,
+    CreateDigitalOutput,
+    CreateAnalogInput,
+    CreateDutyCycleEncoder,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::io")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set(value: bool) -> Self {
+        Self {
+            kind: CommandType::Set,
+            data: Box::into_raw(Box::new(value)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_digital_input() -> Self {
+        Self {
+            kind: CommandType::CreateDigitalInput,
+            data: ptr::null(),
+        }
+    }
+
+    pub(crate) fn create_digital_output() -> Self {
+        Self {
+            kind: CommandType::CreateDigitalOutput,
+            data: ptr::null(),
+        }
+    }
+
+    pub(crate) fn create_analog_input(config: io::AnalogInputConfig) -> Self {
+        Self {
+            kind: CommandType::CreateAnalogInput,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_duty_cycle_encoder(config: io::DutyCycleEncoderConfig) -> Self {
+        Self {
+            kind: CommandType::CreateDutyCycleEncoder,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::Set => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut bool));
+            },
+            CommandType::CreateAnalogInput => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut io::AnalogInputConfig));
+            },
+            CommandType::CreateDutyCycleEncoder => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut io::DutyCycleEncoderConfig));
+            },
+            CommandType::CreateDigitalInput
+            | CommandType::CreateDigitalOutput
+            | CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::io")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct DigitalData {
+    pub(crate) value: bool,
+}
+
+#[ffi_type(namespace = "ffi::device::io")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AnalogData {
+    pub(crate) voltage: f64,
+    pub(crate) average_voltage: f64,
+}
+
+#[ffi_type(namespace = "ffi::device::io")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DutyCycleData {
+    pub(crate) connected: bool,
+    pub(crate) position: f64,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(DigitalData))
+        .register(extra_type!(AnalogData))
+        .register(extra_type!(DutyCycleData))
+}
@@ -0,0 +1,19 @@
+mod analog;
+mod config;
+mod digital;
+mod duty_cycle;
+mod ffi;
+pub mod prelude;
+
+pub use analog::{AnalogData, AnalogInput};
+pub use config::*;
+pub use digital::{DigitalData, DigitalInput, DigitalOutput, Falling, Rising};
+pub use duty_cycle::{DutyCycleData, DutyCycleEncoder};
+use prelude::*;
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder
+}
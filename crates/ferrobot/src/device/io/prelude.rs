@@ -0,0 +1,9 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod io_ffi {
+    pub(crate) use crate::device::io::ffi::*;
+}
+
+pub use crate::device::io::{
+    AnalogInput, DigitalInput, DigitalOutput, DutyCycleEncoder, Falling, Rising,
+};
@@ -0,0 +1,83 @@
+use super::{Rgb, color::Hsv, prelude::*};
+
+/// A built in LED animation, rendered in Rust and sent to the strip every
+/// frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Animation {
+    /// Every pixel set to one color
+    Solid(Rgb),
+    /// A rainbow scrolling along the strip, taking `period` to cycle once
+    Rainbow { period: Time },
+    /// A segment of `width` pixels moving along the strip, taking `period` to
+    /// travel its full length
+    Chase {
+        color: Rgb,
+        background: Rgb,
+        width: usize,
+        period: Time,
+    },
+    /// The whole strip turning on and off, spending half of `period` in each
+    /// state
+    Blink { color: Rgb, period: Time },
+    /// A bar filling `progress` (0.0 to 1.0) of the strip
+    Progress {
+        color: Rgb,
+        background: Rgb,
+        progress: f64,
+    },
+}
+
+impl Animation {
+    /// Whether the animation looks the same on every frame
+    pub(super) fn is_static(&self) -> bool {
+        matches!(self, Self::Solid(_) | Self::Progress { .. })
+    }
+
+    /// Render the frame `elapsed` seconds after the animation started
+    #[allow(clippy::cast_sign_loss)]
+    pub(super) fn render(&self, elapsed: f64, pixels: &mut [Rgb]) {
+        let len = pixels.len();
+
+        if len == 0 {
+            return;
+        }
+
+        match *self {
+            Self::Solid(color) => pixels.fill(color),
+            Self::Rainbow { period } => {
+                let offset = (elapsed / period.get::<second>()).fract() * 360.0;
+
+                for (i, pixel) in pixels.iter_mut().enumerate() {
+                    let hue = i as f64 / len as f64 * 360.0 + offset;
+                    *pixel = Hsv::new(hue, 1.0, 0.5).into();
+                }
+            }
+            Self::Chase {
+                color,
+                background,
+                width,
+                period,
+            } => {
+                let head = ((elapsed / period.get::<second>()).fract() * len as f64) as usize;
+
+                for (i, pixel) in pixels.iter_mut().enumerate() {
+                    let lit = (i + len - head) % len < width;
+                    *pixel = if lit { color } else { background };
+                }
+            }
+            Self::Blink { color, period } => {
+                let on = (elapsed / period.get::<second>()).fract() < 0.5;
+                pixels.fill(if on { color } else { Rgb::OFF });
+            }
+            Self::Progress {
+                color,
+                background,
+                progress,
+            } => {
+                let lit = (progress.clamp(0.0, 1.0) * len as f64).round() as usize;
+                pixels[..lit].fill(color);
+                pixels[lit..].fill(background);
+            }
+        }
+    }
+}
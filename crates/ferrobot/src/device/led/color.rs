@@ -0,0 +1,64 @@
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::led")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLUE: Self = Self::new(0, 0, 255);
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const OFF: Self = Self::new(0, 0, 0);
+    pub const RED: Self = Self::new(255, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    /// Hue, in degrees
+    pub h: f64,
+    /// Saturation, from 0.0 to 1.0
+    pub s: f64,
+    /// Value (brightness), from 0.0 to 1.0
+    pub v: f64,
+}
+
+impl Hsv {
+    #[must_use]
+    pub const fn new(h: f64, s: f64, v: f64) -> Self {
+        Self { h, s, v }
+    }
+}
+
+impl From<Hsv> for Rgb {
+    #[allow(clippy::cast_sign_loss)]
+    fn from(hsv: Hsv) -> Self {
+        let saturation = hsv.s.clamp(0.0, 1.0);
+        let value = hsv.v.clamp(0.0, 1.0);
+        let sector = hsv.h.rem_euclid(360.0) / 60.0;
+
+        let chroma = value * saturation;
+        let secondary = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let min = value - chroma;
+
+        let (red, green, blue) = match sector as u8 {
+            0 => (chroma, secondary, 0.0),
+            1 => (secondary, chroma, 0.0),
+            2 => (0.0, chroma, secondary),
+            3 => (0.0, secondary, chroma),
+            4 => (secondary, 0.0, chroma),
+            _ => (chroma, 0.0, secondary),
+        };
+
+        let channel = |component: f64| ((component + min) * 255.0).round() as u8;
+        Self::new(channel(red), channel(green), channel(blue))
+    }
+}
@@ -0,0 +1,103 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::led")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetData,
+    SetLength,
+    Create,
+    Destroy,
+}
+
+/// A buffer of pixels, one per LED on the strip
+#[ffi_type(namespace = "ffi::device::led")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Pixels {
+    data: *const led::Rgb,
+    len: usize,
+}
+
+#[ffi_type(namespace = "ffi::device::led")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_data(pixels: &[led::Rgb]) -> Self {
+        let pixels: Box<[led::Rgb]> = pixels.into();
+        let len = pixels.len();
+
+        Self {
+            kind: CommandType::SetData,
+            data: Box::into_raw(Box::new(Pixels {
+                data: Box::into_raw(pixels).cast(),
+                len,
+            })) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_length(length: usize) -> Self {
+        Self {
+            kind: CommandType::SetLength,
+            data: Box::into_raw(Box::new(length)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create(length: usize) -> Self {
+        Self {
+            kind: CommandType::Create,
+            data: Box::into_raw(Box::new(length)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetData => unsafe {
+                let pixels = Box::from_raw(self.data as *mut Pixels);
+                mem::drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                    pixels.data.cast_mut(),
+                    pixels.len,
+                )));
+            },
+            CommandType::SetLength | CommandType::Create => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut usize));
+            },
+            CommandType::Destroy => {}
+        }
+    }
+}
+
+// the command owns its payload, so it can be sent to the animation task
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::led")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Data {
+    pub(crate) length: usize,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Pixels))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
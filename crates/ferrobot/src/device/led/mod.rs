@@ -0,0 +1,201 @@
+mod animation;
+mod color;
+mod ffi;
+pub mod prelude;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+pub use animation::Animation;
+use async_lock::Mutex;
+pub use color::{Hsv, Rgb};
+use prelude::*;
+
+use crate::executor::{self, JoinHandle};
+
+/// The most LEDs the roboRIO can drive
+pub const MAX_LENGTH: usize = 5460;
+
+/// How often animations render a new frame
+const FRAME_PERIOD: Duration = Duration::from_millis(20);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Data {
+    pub length: usize,
+}
+
+impl<'a> From<&'a led_ffi::Data> for Data {
+    fn from(value: &'a led_ffi::Data) -> Self {
+        Self {
+            length: value.length,
+        }
+    }
+}
+
+/// An addressable LED strip (e.g. WS2812) on a roboRIO PWM channel. The
+/// roboRIO only supports one strip at a time.
+#[derive(Debug)]
+pub struct AddressableLed {
+    channel: u8,
+    length: AtomicUsize,
+    animation: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AddressableLed {
+    pub async fn new(channel: u8, length: usize) -> Result<Arc<Self>, Error> {
+        if !(1..=MAX_LENGTH).contains(&length) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid strip length: Expected 1 to {MAX_LENGTH}, got {length}"),
+            ));
+        }
+
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            channel,
+            length: AtomicUsize::new(length),
+            animation: Mutex::new(None),
+        });
+        let command = led_ffi::Command::create(length);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub fn length(&self) -> usize {
+        self.length.load(Ordering::Acquire)
+    }
+
+    pub async fn set_length(&self, length: usize) -> Result<(), Error> {
+        if !(1..=MAX_LENGTH).contains(&length) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid strip length: Expected 1 to {MAX_LENGTH}, got {length}"),
+            )
+            .with_device(self.into()));
+        }
+
+        debug!("Setting led strip {} length to {}", self.channel, length);
+
+        let ctx = DeviceContext::current();
+        let command = led_ffi::Command::set_length(length);
+
+        ctx.command(self, command).await?;
+        self.length.store(length, Ordering::Release);
+        Ok(())
+    }
+
+    /// Set every pixel on the strip, stopping any running animation
+    pub async fn set_pixels(&self, pixels: &[Rgb]) -> Result<(), Error> {
+        self.stop().await;
+        self.write(pixels).await
+    }
+
+    /// Set the whole strip to one color, stopping any running animation
+    pub async fn fill(&self, color: impl Into<Rgb>) -> Result<(), Error> {
+        let pixels = vec![color.into(); self.length()];
+        self.set_pixels(&pixels).await
+    }
+
+    /// Start an animation, replacing any running animation
+    pub async fn animate(self: &Arc<Self>, animation: Animation) {
+        // held until the new animation is stored, so concurrent calls can't
+        // both start one and leave the other running unowned
+        let mut running = self.animation.lock().await;
+
+        if let Some(handle) = running.take() {
+            handle.cancel().await;
+        }
+
+        debug!(
+            "Starting led strip {} animation {:?}",
+            self.channel, animation
+        );
+
+        let this = Arc::downgrade(self);
+        let handle = Runtime::current().spawn(async move {
+            let start = Instant::now();
+            let mut pixels = Vec::new();
+
+            while let Some(led) = this.upgrade() {
+                pixels.resize(led.length(), Rgb::OFF);
+                animation.render(start.elapsed().as_secs_f64(), &mut pixels);
+
+                if let Err(err) = led.write(&pixels).await {
+                    warn!("Failed to write led strip {} frame: {err}", led.channel);
+                }
+
+                if animation.is_static() {
+                    break;
+                }
+
+                drop(led);
+                executor::sleep(FRAME_PERIOD).await;
+            }
+        });
+
+        *running = Some(handle);
+    }
+
+    /// Stop the running animation, leaving the last frame on the strip
+    pub async fn stop(&self) {
+        let handle = self.animation.lock().await.take();
+
+        if let Some(handle) = handle {
+            handle.cancel().await;
+        }
+    }
+
+    async fn write(&self, pixels: &[Rgb]) -> Result<(), Error> {
+        let length = self.length();
+
+        if pixels.len() != length {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Pixel buffer length mismatch: Expected {length}, got {}",
+                    pixels.len()
+                ),
+            )
+            .with_device(self.into()));
+        }
+
+        let ctx = DeviceContext::current();
+        let command = led_ffi::Command::set_data(pixels);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for AddressableLed {
+    type CommandFFI = led_ffi::Command;
+    type DataFFI = led_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Pwm;
+    const TYPE: device_ffi::Type = device_ffi::Type::AddressableLed;
+}
+
+impl device::Device for AddressableLed {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for AddressableLed {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder.register(extra_type!(Rgb))
+}
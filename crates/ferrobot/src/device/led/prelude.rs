@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod led_ffi {
+    pub(crate) use crate::device::led::ffi::*;
+}
+
+pub use crate::device::led::{AddressableLed, Animation, Hsv, Rgb};
@@ -1,35 +1,176 @@
+pub mod can;
+pub mod cancoder;
 pub(crate) mod ctx;
 mod ffi;
+mod history;
+pub mod io;
+pub mod led;
+pub mod pigeon;
+pub mod pneumatics;
+mod policy;
+pub mod power;
 pub mod prelude;
+pub mod pwm;
 pub mod spark;
+mod stale;
+pub mod talonfx;
+pub mod vision;
 
-use std::fmt;
+use std::{ffi::c_void, ptr};
 
+use derive_more::{Deref, DerefMut};
+pub use ffi::{Bus, Device as DeviceId, Type as DeviceType};
+pub(crate) use ffi::{Error as FFIError, ErrorType as FFIErrorType};
+pub(crate) use history::History;
+pub use history::{HistoryConfig, Interpolate, interpolate_wrapped, nearest};
+pub use policy::{CommandPolicy, set_command_policy};
 use prelude::*;
+pub use stale::{DeviceStale, Stale, set_stale_threshold};
+
+use crate::{Error, ErrorKind, control::event::Emitter};
 
 pub(crate) trait DeviceFFI {
     const TYPE: device_ffi::Type;
+    const BUS: device_ffi::Bus;
 
-    type DataFFI;
+    type DataFFI: PartialEq;
     type CommandFFI: Command;
+
+    /// The command that releases the device on the robot, or `None` if the
+    /// device doesn't exist there
+    fn destroy_command(&self) -> Option<Self::CommandFFI> {
+        Some(Self::CommandFFI::destroy())
+    }
 }
 
 pub(crate) trait Command {
-    type Error: fmt::Debug;
-    type Ok;
+    type Ok: Decode;
+
+    fn destroy() -> Self;
 }
 
+/// A value the robot responds to a command with
+pub(crate) trait Decode: Sized {
+    /// Take ownership of `data`, which was malloc'd by the robot
+    unsafe fn decode(data: *const c_void) -> Self;
+}
+
+impl Decode for () {
+    unsafe fn decode(data: *const c_void) -> Self {
+        unsafe { libc::free(data.cast_mut()) }
+    }
+}
+
+/// A device on the robot. Commands to a device, e.g. setting a motor's
+/// output, are handed to the robot in batches: right away when issued from a
+/// periodic function or command, or on the next tick, up to a robot loop
+/// (20ms) later, when issued from anywhere else.
 #[allow(private_bounds)]
 pub trait Device: DeviceFFI + Send + Sync + 'static {
     type Data: for<'a> From<&'a Self::DataFFI> + Send + Sync + 'static;
 
     fn id(&self) -> u8;
+
+    /// The channel on the device's module, for devices that share a module id
+    fn channel(&self) -> u8 {
+        0
+    }
+
+    /// The latest data supplied by the robot, along with when it was read
+    async fn data(&self) -> Option<Timestamped<Self::Data>>
+    where
+        Self: Sized,
+    {
+        DeviceContext::current().data(self).await
+    }
+
+    /// Keep a history of this device's data, so past readings can be looked
+    /// up with [`Device::data_at`]
+    async fn keep_history(&self, config: HistoryConfig)
+    where
+        Self: Sized,
+    {
+        DeviceContext::current().keep_history(self, config).await;
+    }
+
+    /// The data at an FPGA `timestamp`, interpolated from the device's
+    /// history. Only the latest reading is available unless
+    /// [`Device::keep_history`] was called. Outside the history, the oldest or
+    /// latest reading is returned with the time it was taken.
+    async fn data_at(&self, timestamp: Time) -> Option<Timestamped<Self::Data>>
+    where
+        Self: Sized,
+        Self::Data: Interpolate,
+    {
+        DeviceContext::current().data_at(self, timestamp).await
+    }
+
+    /// Release the device on the robot and stop supplying its data, so a
+    /// device with the same id can be created again, e.g. to reinitialise a
+    /// mechanism after a fault. Callbacks registered on the device are
+    /// removed.
+    ///
+    /// Dropping the last reference to a device also releases it.
+    async fn close(&self) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        DeviceContext::current()
+            .release(self.into(), ptr::from_ref(self).addr(), false)
+            .await?;
+        Emitter::current().unregister(self).await;
+
+        Ok(())
+    }
+}
+
+/// Release a dropped device in the background, unless it has been closed
+pub(crate) fn release<D: Device>(device: &D) {
+    let id = device.into();
+    let owner = ptr::from_ref(device).addr();
+
+    Runtime::current().spawn(async move {
+        match DeviceContext::current().release(id, owner, true).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => warn!("{err}"),
+        }
+    });
+}
+
+/// A reading along with the FPGA time it was taken
+#[derive(Clone, Copy, Debug, PartialEq, Deref, DerefMut)]
+pub struct Timestamped<T> {
+    #[deref]
+    #[deref_mut]
+    pub data: T,
+    pub timestamp: Time,
+}
+
+impl<T> Timestamped<T> {
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// How long ago the reading was taken, relative to `now`
+    pub fn age(&self, now: Time) -> Time {
+        now - self.timestamp
+    }
 }
 
 #[cfg(feature = "build")]
 pub(crate) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
     builder = ffi::__ffi_inventory(builder);
+    builder = cancoder::__ffi_inventory(builder);
+    builder = io::__ffi_inventory(builder);
+    builder = led::__ffi_inventory(builder);
+    builder = pigeon::__ffi_inventory(builder);
+    builder = pneumatics::__ffi_inventory(builder);
+    builder = power::__ffi_inventory(builder);
+    builder = pwm::__ffi_inventory(builder);
     builder = spark::__ffi_inventory(builder);
+    builder = talonfx::__ffi_inventory(builder);
+    builder = vision::__ffi_inventory(builder);
 
     builder
 }
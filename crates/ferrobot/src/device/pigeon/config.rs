@@ -0,0 +1,29 @@
+use typed_builder::TypedBuilder;
+use uom::si::angle::degree;
+
+use super::prelude::*;
+
+/// The orientation of the Pigeon2 on the robot, in degrees
+#[ffi_type(namespace = "ffi::device::pigeon::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct Pigeon2Config {
+    #[builder(default = 0.0, setter(transform = |yaw: Angle| yaw.get::<degree>()))]
+    pub mount_yaw: f64,
+
+    #[builder(default = 0.0, setter(transform = |pitch: Angle| pitch.get::<degree>()))]
+    pub mount_pitch: f64,
+
+    #[builder(default = 0.0, setter(transform = |roll: Angle| roll.get::<degree>()))]
+    pub mount_roll: f64,
+}
+
+impl Default for Pigeon2Config {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder.register(extra_type!(Pigeon2Config))
+}
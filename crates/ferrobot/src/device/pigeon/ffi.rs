@@ -0,0 +1,84 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::pigeon")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetYaw,
+    Create,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::pigeon")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_yaw(yaw: f64) -> Self {
+        Self {
+            kind: CommandType::SetYaw,
+            data: Box::into_raw(Box::new(yaw)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create(config: pigeon::Pigeon2Config) -> Self {
+        Self {
+            kind: CommandType::Create,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetYaw => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut f64));
+            },
+            CommandType::Create => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut pigeon::Pigeon2Config));
+            },
+            CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::pigeon")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Data {
+    pub(crate) connected: bool,
+    pub(crate) yaw: f64,
+    pub(crate) pitch: f64,
+    pub(crate) roll: f64,
+    pub(crate) angular_velocity_x: f64,
+    pub(crate) angular_velocity_y: f64,
+    pub(crate) angular_velocity_z: f64,
+    pub(crate) acceleration_x: f64,
+    pub(crate) acceleration_y: f64,
+    pub(crate) acceleration_z: f64,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
@@ -0,0 +1,121 @@
+mod config;
+mod ffi;
+pub mod prelude;
+
+pub use config::*;
+use prelude::*;
+use uom::si::{acceleration::standard_gravity, angle::degree, angular_velocity::degree_per_second};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Data {
+    pub connected: bool,
+    /// Heading of the robot, counter-clockwise positive. Continuous, so it
+    /// does not wrap at 360 degrees.
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub roll: Angle,
+    /// Angular velocity about the world frame x, y and z axes
+    pub angular_velocity: [AngularVelocity; 3],
+    /// Acceleration along the device's own x, y and z axes, including
+    /// gravity. Unlike the angular velocity, this isn't rotated into the world
+    /// frame, so it depends on how the Pigeon is mounted.
+    pub acceleration: [Acceleration; 3],
+}
+
+impl<'a> From<&'a pigeon_ffi::Data> for Data {
+    fn from(value: &'a pigeon_ffi::Data) -> Self {
+        Self {
+            connected: value.connected,
+            yaw: Angle::new::<degree>(value.yaw),
+            pitch: Angle::new::<degree>(value.pitch),
+            roll: Angle::new::<degree>(value.roll),
+            angular_velocity: [
+                value.angular_velocity_x,
+                value.angular_velocity_y,
+                value.angular_velocity_z,
+            ]
+            .map(AngularVelocity::new::<degree_per_second>),
+            acceleration: [
+                value.acceleration_x,
+                value.acceleration_y,
+                value.acceleration_z,
+            ]
+            .map(Acceleration::new::<standard_gravity>),
+        }
+    }
+}
+
+impl Interpolate for Data {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            connected: self.connected.interpolate(&other.connected, t),
+            yaw: self.yaw.interpolate(&other.yaw, t),
+            pitch: self.pitch.interpolate(&other.pitch, t),
+            roll: self.roll.interpolate(&other.roll, t),
+            angular_velocity: self
+                .angular_velocity
+                .interpolate(&other.angular_velocity, t),
+            acceleration: self.acceleration.interpolate(&other.acceleration, t),
+        }
+    }
+}
+
+/// A CTRE `Pigeon2` IMU
+#[derive(Debug)]
+pub struct Pigeon2 {
+    can_id: u8,
+}
+
+impl Pigeon2 {
+    pub async fn new(can_id: u8, config: Pigeon2Config) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { can_id });
+        let command = pigeon_ffi::Command::create(config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    /// Reset the heading, e.g. to zero at the start of a match
+    pub async fn set_yaw(&self, yaw: Angle) -> Result<(), Error> {
+        debug!("Setting pigeon2 {} yaw to {:?}", self.can_id, yaw);
+
+        let yaw = yaw.get::<degree>();
+        let ctx = DeviceContext::current();
+        let command = pigeon_ffi::Command::set_yaw(yaw);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for Pigeon2 {
+    type CommandFFI = pigeon_ffi::Command;
+    type DataFFI = pigeon_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::Pigeon2;
+}
+
+impl device::Device for Pigeon2 {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.can_id
+    }
+}
+
+impl Drop for Pigeon2 {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder
+}
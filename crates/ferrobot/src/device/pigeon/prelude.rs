@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod pigeon_ffi {
+    pub(crate) use crate::device::pigeon::ffi::*;
+}
+
+pub use crate::device::pigeon::Pigeon2;
@@ -0,0 +1,93 @@
+use uom::si::pressure::psi;
+
+use super::{CompressorConfig, Error, Module, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompressorData {
+    pub enabled: bool,
+    /// Whether the digital pressure switch is closed, i.e. the system is
+    /// below pressure
+    pub pressure_switch: bool,
+    /// Pressure read by the analog pressure sensor. Always zero on a CTRE
+    /// Pneumatics Control Module.
+    pub pressure: Pressure,
+    pub current: ElectricCurrent,
+}
+
+impl<'a> From<&'a pneumatics_ffi::CompressorData> for CompressorData {
+    fn from(value: &'a pneumatics_ffi::CompressorData) -> Self {
+        Self {
+            enabled: value.enabled,
+            pressure_switch: value.pressure_switch,
+            pressure: Pressure::new::<psi>(value.pressure),
+            current: ElectricCurrent::new::<amp>(value.current),
+        }
+    }
+}
+
+impl Interpolate for CompressorData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            enabled: self.enabled.interpolate(&other.enabled, t),
+            pressure_switch: self.pressure_switch.interpolate(&other.pressure_switch, t),
+            pressure: self.pressure.interpolate(&other.pressure, t),
+            current: self.current.interpolate(&other.current, t),
+        }
+    }
+}
+
+/// The compressor attached to a pneumatics module
+#[derive(Debug)]
+pub struct Compressor {
+    module: Module,
+}
+
+impl Compressor {
+    pub async fn new(module: Module, config: CompressorConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { module });
+        let command = pneumatics_ffi::Command::create_compressor(module.kind, config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    /// Change how the compressor is controlled, e.g. to disable it during a
+    /// match
+    pub async fn configure(&self, config: CompressorConfig) -> Result<(), Error> {
+        debug!(
+            "Setting compressor {} mode to {:?}",
+            self.module.id, config.mode
+        );
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::configure(config);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for Compressor {
+    type CommandFFI = pneumatics_ffi::Command;
+    type DataFFI = pneumatics_ffi::CompressorData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::Compressor;
+}
+
+impl device::Device for Compressor {
+    type Data = CompressorData;
+
+    fn id(&self) -> u8 {
+        self.module.id
+    }
+}
+
+impl Drop for Compressor {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
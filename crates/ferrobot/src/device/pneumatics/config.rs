@@ -0,0 +1,99 @@
+use typed_builder::TypedBuilder;
+use uom::si::pressure::psi;
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::pneumatics::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModuleType {
+    /// A CTRE Pneumatics Control Module, with 8 solenoid channels
+    CtrePcm = 0,
+    /// A REV Pneumatic Hub, with 16 solenoid channels and an analog pressure
+    /// sensor
+    RevPh = 1,
+}
+
+impl ModuleType {
+    /// The number of solenoid channels on this type of module
+    #[must_use]
+    pub const fn channels(self) -> u8 {
+        match self {
+            Self::CtrePcm => 8,
+            Self::RevPh => 16,
+        }
+    }
+}
+
+/// A pneumatics module on the CAN bus
+#[ffi_type(namespace = "ffi::device::pneumatics::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Module {
+    pub kind: ModuleType,
+    pub id: u8,
+}
+
+impl Module {
+    #[must_use]
+    pub const fn ctre_pcm(id: u8) -> Self {
+        Self {
+            kind: ModuleType::CtrePcm,
+            id,
+        }
+    }
+
+    #[must_use]
+    pub const fn rev_ph(id: u8) -> Self {
+        Self {
+            kind: ModuleType::RevPh,
+            id,
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompressorMode {
+    /// The compressor does not run
+    Disabled = 0,
+    /// The compressor runs until the digital pressure switch opens
+    Digital = 1,
+    /// The compressor runs between the min and max pressure, as read by the
+    /// analog pressure sensor. Only supported on the REV Pneumatic Hub.
+    Analog = 2,
+    /// The compressor runs between the min and max pressure, and stops early
+    /// if the digital pressure switch opens. Only supported on the REV
+    /// Pneumatic Hub.
+    Hybrid = 3,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct CompressorConfig {
+    #[builder(default = CompressorMode::Digital)]
+    pub mode: CompressorMode,
+
+    /// The pressure to turn the compressor on at, in psi. Ignored in digital
+    /// mode.
+    #[builder(default = 80.0, setter(transform = |p: Pressure| p.get::<psi>()))]
+    pub min_pressure: f64,
+
+    /// The pressure to turn the compressor off at, in psi. Ignored in digital
+    /// mode.
+    #[builder(default = 120.0, setter(transform = |p: Pressure| p.get::<psi>()))]
+    pub max_pressure: f64,
+}
+
+impl Default for CompressorConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(ModuleType))
+        .register(extra_type!(Module))
+        .register(extra_type!(CompressorMode))
+        .register(extra_type!(CompressorConfig))
+}
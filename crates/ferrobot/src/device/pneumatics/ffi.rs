@@ -0,0 +1,189 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    Set,
+    SetDouble,
+    Toggle,
+    Pulse,
+    Configure,
+    CreateSolenoid,
+    CreateDoubleSolenoid,
+    CreateCompressor,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CreateDoubleSolenoid {
+    module_type: pneumatics::ModuleType,
+    reverse_channel: u8,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CreateCompressor {
+    module_type: pneumatics::ModuleType,
+    config: pneumatics::CompressorConfig,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set(value: bool) -> Self {
+        Self {
+            kind: CommandType::Set,
+            data: Box::into_raw(Box::new(value)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_double(value: pneumatics::DoubleSolenoidValue) -> Self {
+        Self {
+            kind: CommandType::SetDouble,
+            data: Box::into_raw(Box::new(value)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn toggle() -> Self {
+        Self {
+            kind: CommandType::Toggle,
+            data: ptr::null(),
+        }
+    }
+
+    pub(crate) fn pulse(seconds: f64) -> Self {
+        Self {
+            kind: CommandType::Pulse,
+            data: Box::into_raw(Box::new(seconds)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn configure(config: pneumatics::CompressorConfig) -> Self {
+        Self {
+            kind: CommandType::Configure,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_solenoid(module_type: pneumatics::ModuleType) -> Self {
+        Self {
+            kind: CommandType::CreateSolenoid,
+            data: Box::into_raw(Box::new(module_type)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_double_solenoid(
+        module_type: pneumatics::ModuleType,
+        reverse_channel: u8,
+    ) -> Self {
+        Self {
+            kind: CommandType::CreateDoubleSolenoid,
+            data: Box::into_raw(Box::new(CreateDoubleSolenoid {
+                module_type,
+                reverse_channel,
+            })) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_compressor(
+        module_type: pneumatics::ModuleType,
+        config: pneumatics::CompressorConfig,
+    ) -> Self {
+        Self {
+            kind: CommandType::CreateCompressor,
+            data: Box::into_raw(Box::new(CreateCompressor {
+                module_type,
+                config,
+            })) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::Set => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut bool));
+            },
+            CommandType::SetDouble => unsafe {
+                mem::drop(Box::from_raw(
+                    self.data as *mut pneumatics::DoubleSolenoidValue,
+                ));
+            },
+            CommandType::Pulse => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut f64));
+            },
+            CommandType::Configure => unsafe {
+                mem::drop(Box::from_raw(
+                    self.data as *mut pneumatics::CompressorConfig,
+                ));
+            },
+            CommandType::CreateSolenoid => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut pneumatics::ModuleType));
+            },
+            CommandType::CreateDoubleSolenoid => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut CreateDoubleSolenoid));
+            },
+            CommandType::CreateCompressor => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut CreateCompressor));
+            },
+            CommandType::Toggle | CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct SolenoidData {
+    pub(crate) value: bool,
+    pub(crate) disabled: bool,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct DoubleSolenoidData {
+    pub(crate) value: pneumatics::DoubleSolenoidValue,
+    pub(crate) disabled: bool,
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CompressorData {
+    pub(crate) enabled: bool,
+    pub(crate) pressure_switch: bool,
+    pub(crate) pressure: f64,
+    pub(crate) current: f64,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(CreateDoubleSolenoid))
+        .register(extra_type!(CreateCompressor))
+        .register(extra_type!(Command))
+        .register(extra_type!(SolenoidData))
+        .register(extra_type!(DoubleSolenoidData))
+        .register(extra_type!(CompressorData))
+}
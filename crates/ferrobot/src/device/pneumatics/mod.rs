@@ -0,0 +1,35 @@
+mod compressor;
+mod config;
+mod ffi;
+pub mod prelude;
+mod solenoid;
+
+pub use compressor::{Compressor, CompressorData};
+pub use config::*;
+use prelude::*;
+pub use solenoid::{
+    DoubleSolenoid, DoubleSolenoidData, DoubleSolenoidValue, Solenoid, SolenoidData,
+};
+
+fn check_channel(module: Module, channel: u8) -> Result<(), Error> {
+    if channel >= module.kind.channels() {
+        return Err(Error::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Invalid channel {channel} for a {:?}: Expected 0 to {}",
+                module.kind,
+                module.kind.channels() - 1
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder = solenoid::__ffi_inventory(builder);
+    builder
+}
@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod pneumatics_ffi {
+    pub(crate) use crate::device::pneumatics::ffi::*;
+}
+
+pub use crate::device::pneumatics::{Compressor, DoubleSolenoid, Solenoid};
@@ -0,0 +1,246 @@
+use super::{Error, Module, check_channel, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SolenoidData {
+    pub value: bool,
+    /// Whether the channel has been disabled by the module, e.g. due to a
+    /// short circuit
+    pub disabled: bool,
+}
+
+impl<'a> From<&'a pneumatics_ffi::SolenoidData> for SolenoidData {
+    fn from(value: &'a pneumatics_ffi::SolenoidData) -> Self {
+        Self {
+            value: value.value,
+            disabled: value.disabled,
+        }
+    }
+}
+
+impl Interpolate for SolenoidData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            value: self.value.interpolate(&other.value, t),
+            disabled: self.disabled.interpolate(&other.disabled, t),
+        }
+    }
+}
+
+/// A single acting solenoid on a pneumatics module
+#[derive(Debug)]
+pub struct Solenoid {
+    module: Module,
+    channel: u8,
+}
+
+impl Solenoid {
+    pub async fn new(module: Module, channel: u8) -> Result<Arc<Self>, Error> {
+        check_channel(module, channel)?;
+
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { module, channel });
+        let command = pneumatics_ffi::Command::create_solenoid(module.kind);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set(&self, value: bool) -> Result<(), Error> {
+        debug!(
+            "Setting solenoid {}:{} to {}",
+            self.module.id, self.channel, value
+        );
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::set(value);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn toggle(&self) -> Result<(), Error> {
+        debug!("Toggling solenoid {}:{}", self.module.id, self.channel);
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::toggle();
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    /// Turn the solenoid on for a duration, timed by the pneumatics module
+    pub async fn pulse(&self, duration: Time) -> Result<(), Error> {
+        debug!(
+            "Pulsing solenoid {}:{} for {:?}",
+            self.module.id, self.channel, duration
+        );
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::pulse(duration.get::<second>());
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for Solenoid {
+    type CommandFFI = pneumatics_ffi::Command;
+    type DataFFI = pneumatics_ffi::SolenoidData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::Solenoid;
+}
+
+impl device::Device for Solenoid {
+    type Data = SolenoidData;
+
+    fn id(&self) -> u8 {
+        self.module.id
+    }
+
+    fn channel(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for Solenoid {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::pneumatics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DoubleSolenoidValue {
+    Off = 0,
+    Forward = 1,
+    Reverse = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DoubleSolenoidData {
+    pub value: DoubleSolenoidValue,
+    /// Whether either channel has been disabled by the module, e.g. due to a
+    /// short circuit
+    pub disabled: bool,
+}
+
+impl<'a> From<&'a pneumatics_ffi::DoubleSolenoidData> for DoubleSolenoidData {
+    fn from(value: &'a pneumatics_ffi::DoubleSolenoidData) -> Self {
+        Self {
+            value: value.value,
+            disabled: value.disabled,
+        }
+    }
+}
+
+impl Interpolate for DoubleSolenoidData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            value: *device::nearest(&self.value, &other.value, t),
+            disabled: self.disabled.interpolate(&other.disabled, t),
+        }
+    }
+}
+
+/// A double acting solenoid on a pneumatics module, driven by a forward and
+/// reverse channel
+#[derive(Debug)]
+pub struct DoubleSolenoid {
+    module: Module,
+    forward_channel: u8,
+    reverse_channel: u8,
+}
+
+impl DoubleSolenoid {
+    pub async fn new(
+        module: Module,
+        forward_channel: u8,
+        reverse_channel: u8,
+    ) -> Result<Arc<Self>, Error> {
+        check_channel(module, forward_channel)?;
+        check_channel(module, reverse_channel)?;
+
+        if forward_channel == reverse_channel {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Forward and reverse channels of a double solenoid must differ, got {forward_channel} for both"
+                ),
+            ));
+        }
+
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            module,
+            forward_channel,
+            reverse_channel,
+        });
+        let command = pneumatics_ffi::Command::create_double_solenoid(module.kind, reverse_channel);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set(&self, value: DoubleSolenoidValue) -> Result<(), Error> {
+        debug!(
+            "Setting double solenoid {}:{}/{} to {:?}",
+            self.module.id, self.forward_channel, self.reverse_channel, value
+        );
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::set_double(value);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    /// Switch between forward and reverse. Does nothing if the solenoid is off.
+    pub async fn toggle(&self) -> Result<(), Error> {
+        debug!(
+            "Toggling double solenoid {}:{}/{}",
+            self.module.id, self.forward_channel, self.reverse_channel
+        );
+
+        let ctx = DeviceContext::current();
+        let command = pneumatics_ffi::Command::toggle();
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for DoubleSolenoid {
+    type CommandFFI = pneumatics_ffi::Command;
+    type DataFFI = pneumatics_ffi::DoubleSolenoidData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::DoubleSolenoid;
+}
+
+impl device::Device for DoubleSolenoid {
+    type Data = DoubleSolenoidData;
+
+    fn id(&self) -> u8 {
+        self.module.id
+    }
+
+    fn channel(&self) -> u8 {
+        self.forward_channel
+    }
+}
+
+impl Drop for DoubleSolenoid {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder.register(extra_type!(DoubleSolenoidValue))
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use typed_builder::TypedBuilder;
+
+use super::prelude::*;
+
+/// How commands to a type of device are timed out and retried
+#[derive(Clone, Copy, Debug, TypedBuilder)]
+pub struct CommandPolicy {
+    /// How long to wait for the robot to handle each attempt at a command.
+    /// Commands issued outside a tick wait for the next one before they are
+    /// handled, so this should be a few loop periods at least.
+    ///
+    /// Timing out only stops waiting. The robot handles commands with
+    /// blocking vendor calls that can't be interrupted, so a slow call still
+    /// holds up the rest of the tick until it returns.
+    #[builder(default = Duration::from_millis(500))]
+    pub timeout: Duration,
+
+    /// How many times to retry a command that failed with a transient error,
+    /// i.e. a timeout or CAN fault reported by the vendor library
+    #[builder(default = 2)]
+    pub retries: u32,
+
+    /// How long to wait before each retry
+    #[builder(default = Duration::from_millis(20))]
+    pub retry_delay: Duration,
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Set how commands to every device of type `kind` are timed out and retried.
+/// Applies to commands issued after the call.
+pub fn set_command_policy(kind: device::DeviceType, policy: CommandPolicy) {
+    DeviceContext::current().set_command_policy(kind, policy);
+}
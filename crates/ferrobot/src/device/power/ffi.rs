@@ -0,0 +1,136 @@
+use std::{ffi::c_void, mem, ptr, slice};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::power")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetSwitchableChannel,
+    ClearStickyFaults,
+    ResetTotalEnergy,
+    Create,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::power")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_switchable_channel(enabled: bool) -> Self {
+        Self {
+            kind: CommandType::SetSwitchableChannel,
+            data: Box::into_raw(Box::new(enabled)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn clear_sticky_faults() -> Self {
+        Self {
+            kind: CommandType::ClearStickyFaults,
+            data: ptr::null(),
+        }
+    }
+
+    pub(crate) fn reset_total_energy() -> Self {
+        Self {
+            kind: CommandType::ResetTotalEnergy,
+            data: ptr::null(),
+        }
+    }
+
+    pub(crate) fn create(module_type: power::ModuleType) -> Self {
+        Self {
+            kind: CommandType::Create,
+            data: Box::into_raw(Box::new(module_type)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetSwitchableChannel => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut bool));
+            },
+            CommandType::Create => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut power::ModuleType));
+            },
+            CommandType::ClearStickyFaults
+            | CommandType::ResetTotalEnergy
+            | CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::power")]
+#[derive(Debug)]
+pub(crate) struct Data {
+    pub(crate) voltage: f64,
+    pub(crate) temperature: f64,
+    pub(crate) total_current: f64,
+    pub(crate) total_power: f64,
+    pub(crate) total_energy: f64,
+    /// Malloc'd by the robot, one entry per channel
+    currents: *const f64,
+    channels: usize,
+    /// Bit n is set if the breaker on channel n has tripped
+    pub(crate) breaker_faults: u32,
+    pub(crate) brownout: bool,
+    pub(crate) can_warning: bool,
+    pub(crate) hardware_fault: bool,
+}
+
+impl Data {
+    pub(crate) fn currents(&self) -> &[f64] {
+        if self.currents.is_null() {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(self.currents, self.channels) }
+    }
+}
+
+impl PartialEq for Data {
+    fn eq(&self, other: &Self) -> bool {
+        self.voltage == other.voltage
+            && self.temperature == other.temperature
+            && self.total_current == other.total_current
+            && self.total_power == other.total_power
+            && self.total_energy == other.total_energy
+            && self.currents() == other.currents()
+            && self.breaker_faults == other.breaker_faults
+            && self.brownout == other.brownout
+            && self.can_warning == other.can_warning
+            && self.hardware_fault == other.hardware_fault
+    }
+}
+
+impl Drop for Data {
+    fn drop(&mut self) {
+        unsafe { libc::free(self.currents as *mut _) }
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
@@ -0,0 +1,164 @@
+mod ffi;
+pub mod prelude;
+
+use prelude::*;
+use uom::si::{energy::joule, power::watt, thermodynamic_temperature::degree_celsius};
+
+#[ffi_type(namespace = "ffi::device::power")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModuleType {
+    /// A CTRE Power Distribution Panel, with 16 channels. Usually CAN id 0.
+    Ctre = 0,
+    /// A REV Power Distribution Hub, with 24 channels and a switchable
+    /// channel. Usually CAN id 1.
+    Rev = 1,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Data {
+    /// Input voltage from the battery
+    pub voltage: ElectricPotential,
+    pub temperature: ThermodynamicTemperature,
+    pub total_current: ElectricCurrent,
+    pub total_power: Power,
+    /// Energy used since the robot started, or since the last reset
+    pub total_energy: Energy,
+    /// Current through each channel, indexed by channel number
+    pub currents: Vec<ElectricCurrent>,
+    /// Channels with a tripped breaker
+    pub breaker_faults: Vec<u8>,
+    pub brownout: bool,
+    pub can_warning: bool,
+    pub hardware_fault: bool,
+}
+
+impl Data {
+    /// The current through a channel, or `None` if the channel does not exist
+    #[must_use]
+    pub fn current(&self, channel: u8) -> Option<ElectricCurrent> {
+        self.currents.get(channel as usize).copied()
+    }
+
+    /// Whether the breaker on a channel has tripped
+    #[must_use]
+    pub fn breaker_fault(&self, channel: u8) -> bool {
+        self.breaker_faults.contains(&channel)
+    }
+}
+
+impl<'a> From<&'a power_ffi::Data> for Data {
+    fn from(value: &'a power_ffi::Data) -> Self {
+        let currents = value.currents();
+
+        Self {
+            voltage: ElectricPotential::new::<volt>(value.voltage),
+            temperature: ThermodynamicTemperature::new::<degree_celsius>(value.temperature),
+            total_current: ElectricCurrent::new::<amp>(value.total_current),
+            total_power: Power::new::<watt>(value.total_power),
+            total_energy: Energy::new::<joule>(value.total_energy),
+            currents: currents
+                .iter()
+                .map(|&current| ElectricCurrent::new::<amp>(current))
+                .collect(),
+            breaker_faults: (0..32)
+                .filter(|channel| value.breaker_faults & (1 << channel) != 0)
+                .collect(),
+            brownout: value.brownout,
+            can_warning: value.can_warning,
+            hardware_fault: value.hardware_fault,
+        }
+    }
+}
+
+/// A CTRE Power Distribution Panel or REV Power Distribution Hub
+#[derive(Debug)]
+pub struct PowerDistribution {
+    can_id: u8,
+    module_type: ModuleType,
+}
+
+impl PowerDistribution {
+    pub async fn new(can_id: u8, module_type: ModuleType) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            can_id,
+            module_type,
+        });
+        let command = power_ffi::Command::create(module_type);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    /// Turn the switchable channel of a REV Power Distribution Hub on or off
+    pub async fn set_switchable_channel(&self, enabled: bool) -> Result<(), Error> {
+        if self.module_type != ModuleType::Rev {
+            return Err(Error::new(
+                ErrorKind::UnsupportedCommand,
+                "The switchable channel is only available on a REV Power Distribution Hub",
+            )
+            .with_device(self.into()));
+        }
+
+        debug!(
+            "Setting power distribution {} switchable channel to {}",
+            self.can_id, enabled
+        );
+
+        let ctx = DeviceContext::current();
+        let command = power_ffi::Command::set_switchable_channel(enabled);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn clear_sticky_faults(&self) -> Result<(), Error> {
+        debug!("Clearing power distribution {} sticky faults", self.can_id);
+
+        let ctx = DeviceContext::current();
+        let command = power_ffi::Command::clear_sticky_faults();
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn reset_total_energy(&self) -> Result<(), Error> {
+        debug!("Resetting power distribution {} total energy", self.can_id);
+
+        let ctx = DeviceContext::current();
+        let command = power_ffi::Command::reset_total_energy();
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for PowerDistribution {
+    type CommandFFI = power_ffi::Command;
+    type DataFFI = power_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::PowerDistribution;
+}
+
+impl device::Device for PowerDistribution {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.can_id
+    }
+}
+
+impl Drop for PowerDistribution {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder.register(extra_type!(ModuleType))
+}
@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod power_ffi {
+    pub(crate) use crate::device::power::ffi::*;
+}
+
+pub use crate::device::power::PowerDistribution;
@@ -2,8 +2,12 @@ pub(crate) mod device_ffi {
     pub(crate) use crate::device::ffi::*;
 }
 
-pub(crate) use super::ctx::{self as device_ctx, DeviceContext};
+pub(crate) use super::ctx::DeviceContext;
 pub use crate::{
-    device::{self, Device, spark},
+    Error, ErrorKind, Runtime,
+    device::{
+        self, Device, Interpolate, Timestamped, cancoder, io, led, pigeon, pneumatics, power, pwm,
+        spark, talonfx, vision,
+    },
     prelude::*,
 };
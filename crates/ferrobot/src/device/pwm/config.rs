@@ -0,0 +1,35 @@
+use typed_builder::TypedBuilder;
+
+use super::prelude::*;
+
+/// The motor controller driven by a PWM channel. This only affects the pulse
+/// widths sent to the controller.
+#[ffi_type(namespace = "ffi::device::pwm::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Controller {
+    Spark = 0,
+    PwmSparkMax = 1,
+    PwmSparkFlex = 2,
+    VictorSP = 3,
+    PwmVictorSPX = 4,
+    Talon = 5,
+    PwmTalonSRX = 6,
+    PwmTalonFX = 7,
+    PwmVenom = 8,
+}
+
+#[ffi_type(namespace = "ffi::device::pwm::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TypedBuilder)]
+pub struct PwmMotorConfig {
+    pub controller: Controller,
+
+    #[builder(default = false)]
+    pub inverted: bool,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(Controller))
+        .register(extra_type!(PwmMotorConfig))
+}
@@ -0,0 +1,107 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::pwm")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetSpeed,
+    SetAngle,
+    SetPosition,
+    CreateMotor,
+    CreateServo,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::pwm")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_speed(speed: f64) -> Self {
+        Self {
+            kind: CommandType::SetSpeed,
+            data: Box::into_raw(Box::new(speed)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_angle(degrees: f64) -> Self {
+        Self {
+            kind: CommandType::SetAngle,
+            data: Box::into_raw(Box::new(degrees)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_position(position: f64) -> Self {
+        Self {
+            kind: CommandType::SetPosition,
+            data: Box::into_raw(Box::new(position)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_motor(config: pwm::PwmMotorConfig) -> Self {
+        Self {
+            kind: CommandType::CreateMotor,
+            data: Box::into_raw(Box::new(config)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_servo() -> Self {
+        Self {
+            kind: CommandType::CreateServo,
+            data: ptr::null(),
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetSpeed | CommandType::SetAngle | CommandType::SetPosition => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut f64));
+            },
+            CommandType::CreateMotor => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut pwm::PwmMotorConfig));
+            },
+            CommandType::CreateServo | CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::pwm")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MotorData {
+    pub(crate) speed: f64,
+}
+
+#[ffi_type(namespace = "ffi::device::pwm")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ServoData {
+    pub(crate) angle: f64,
+    pub(crate) position: f64,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(MotorData))
+        .register(extra_type!(ServoData))
+}
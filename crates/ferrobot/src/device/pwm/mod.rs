@@ -0,0 +1,203 @@
+mod config;
+mod ffi;
+pub mod prelude;
+
+pub use config::*;
+use prelude::*;
+use uom::si::angle::degree;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotorData {
+    /// The last speed set, from -1.0 to 1.0
+    pub speed: f64,
+}
+
+impl<'a> From<&'a pwm_ffi::MotorData> for MotorData {
+    fn from(value: &'a pwm_ffi::MotorData) -> Self {
+        Self { speed: value.speed }
+    }
+}
+
+impl Interpolate for MotorData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            speed: self.speed.interpolate(&other.speed, t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServoData {
+    /// The last angle set, from 0 to 180 degrees
+    pub angle: Angle,
+    /// The last position set, from 0.0 (full left) to 1.0 (full right)
+    pub position: f64,
+}
+
+impl<'a> From<&'a pwm_ffi::ServoData> for ServoData {
+    fn from(value: &'a pwm_ffi::ServoData) -> Self {
+        Self {
+            angle: Angle::new::<degree>(value.angle),
+            position: value.position,
+        }
+    }
+}
+
+impl Interpolate for ServoData {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            angle: self.angle.interpolate(&other.angle, t),
+            position: self.position.interpolate(&other.position, t),
+        }
+    }
+}
+
+/// A motor controller driven over a roboRIO PWM channel
+#[derive(Debug)]
+pub struct PwmMotor {
+    channel: u8,
+}
+
+impl PwmMotor {
+    pub async fn new(channel: u8, config: PwmMotorConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { channel });
+        let command = pwm_ffi::Command::create_motor(config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set_speed(&self, speed: f64) -> Result<(), Error> {
+        if !(-1.0..=1.0).contains(&speed) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!("Incorrect parameter for `set_speed`: Expected -1.0 to 1.0, got {speed}"),
+            )
+            .with_device(self.into()));
+        }
+
+        debug!("Setting pwm motor {} speed to {}", self.channel, speed);
+
+        let ctx = DeviceContext::current();
+        let command = pwm_ffi::Command::set_speed(speed);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for PwmMotor {
+    type CommandFFI = pwm_ffi::Command;
+    type DataFFI = pwm_ffi::MotorData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Pwm;
+    const TYPE: device_ffi::Type = device_ffi::Type::PwmMotor;
+}
+
+impl device::Device for PwmMotor {
+    type Data = MotorData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for PwmMotor {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+/// A hobby servo on a roboRIO PWM channel
+#[derive(Debug)]
+pub struct Servo {
+    channel: u8,
+}
+
+impl Servo {
+    pub async fn new(channel: u8) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { channel });
+        let command = pwm_ffi::Command::create_servo();
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set_angle(&self, angle: Angle) -> Result<(), Error> {
+        let degrees = angle.get::<degree>();
+
+        if !(0.0..=180.0).contains(&degrees) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Incorrect parameter for `set_angle`: Expected 0 to 180 degrees, got {angle:?}"
+                ),
+            )
+            .with_device(self.into()));
+        }
+
+        debug!("Setting servo {} angle to {:?}", self.channel, angle);
+
+        let ctx = DeviceContext::current();
+        let command = pwm_ffi::Command::set_angle(degrees);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    /// Set the servo position, from 0.0 (full left) to 1.0 (full right)
+    pub async fn set_position(&self, position: f64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&position) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Incorrect parameter for `set_position`: Expected 0.0 to 1.0, got {position}"
+                ),
+            )
+            .with_device(self.into()));
+        }
+
+        debug!("Setting servo {} position to {}", self.channel, position);
+
+        let ctx = DeviceContext::current();
+        let command = pwm_ffi::Command::set_position(position);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for Servo {
+    type CommandFFI = pwm_ffi::Command;
+    type DataFFI = pwm_ffi::ServoData;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Pwm;
+    const TYPE: device_ffi::Type = device_ffi::Type::Servo;
+}
+
+impl device::Device for Servo {
+    type Data = ServoData;
+
+    fn id(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl Drop for Servo {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder
+}
@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod pwm_ffi {
+    pub(crate) use crate::device::pwm::ffi::*;
+}
+
+pub use crate::device::pwm::{PwmMotor, Servo};
@@ -2,11 +2,16 @@ use std::time::Duration;
 
 use typed_builder::TypedBuilder;
 
-use super::{SparkMax, prelude::*};
+use super::prelude::*;
 
 #[ffi_type(namespace = "ffi::device::spark::config")]
 #[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
 pub struct AbsoluteEncoderConfig {
+    /// Whether an absolute encoder is attached. Absolute encoder readings are
+    /// only reported in [`Data`](super::Data) when this is set.
+    #[builder(default = false)]
+    pub enabled: bool,
+
     /// Set the phase of the encoder so that it is in phase with the motor
     /// itself.
     #[builder(default = false)]
@@ -60,12 +65,116 @@ pub struct AbsoluteEncoderConfig {
     pub zero_centered: bool,
 }
 
+#[ffi_type(namespace = "ffi::device::spark::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct AlternateEncoderConfig {
+    /// Whether an alternate encoder is attached. This puts the data port into
+    /// alternate encoder mode, so it cannot be used alongside an absolute
+    /// encoder.
+    #[builder(default = false)]
+    pub enabled: bool,
+
+    /// Set the counts per revolution of the alternate encoder.
+    #[builder(default = 0)]
+    pub counts_per_revolution: u32,
+
+    /// Set the phase of the encoder so that it is in phase with the motor
+    /// itself.
+    #[builder(default = false)]
+    pub inverted: bool,
+
+    /// Set the conversion factor for the position of the alternate encoder.
+    /// The native unit is rotations and values will be multiplied by this
+    /// conversion factor
+    ///
+    /// This is NOT RECOMMENDED, you will break uom outputs if you use this
+    #[builder(default = 1.0)]
+    pub position_factor: f64,
+
+    /// Set the conversion factor for the velocity of the alternate encoder.
+    /// The native unit is rotations per minute and values will be multiplied by
+    /// this conversion factor
+    ///
+    /// This is NOT RECOMMENDED, you will break uom outputs if you use this
+    #[builder(default = 1.0)]
+    pub velocity_factor: f64,
+
+    /// Set the sampling depth of the velocity calculation process of the
+    /// encoder. This value sets the number of samples in the average for
+    /// velocity readings.
+    ///
+    /// This value must be in the range [1, 64]. The default value is 64.
+    #[builder(default = 0, setter(transform = |depth: u8| {
+        assert!(depth.is_power_of_two() && depth <= 64);
+        depth
+    }))]
+    pub average_depth: u8,
+
+    /// Set the position measurement period used to calculate the velocity of
+    /// the encoder. This value must be >= 1ms and <= 100ms, and will be
+    /// interpreted in a whole number of ms. The default value is 100ms.
+    #[builder(default = 0, setter(transform = |d: Duration| d.as_millis() as u8))]
+    pub measurement_period: u8,
+}
+
+#[ffi_type(namespace = "ffi::device::spark::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct ExternalEncoderConfig {
+    /// Whether an external encoder is attached to the Spark Flex.
+    #[builder(default = false)]
+    pub enabled: bool,
+
+    /// Set the counts per revolution of the external encoder.
+    #[builder(default = 0)]
+    pub counts_per_revolution: u32,
+
+    /// Set the phase of the encoder so that it is in phase with the motor
+    /// itself.
+    #[builder(default = false)]
+    pub inverted: bool,
+
+    /// Set the conversion factor for the position of the external encoder.
+    /// The native unit is rotations and values will be multiplied by this
+    /// conversion factor
+    ///
+    /// This is NOT RECOMMENDED, you will break uom outputs if you use this
+    #[builder(default = 1.0)]
+    pub position_factor: f64,
+
+    /// Set the conversion factor for the velocity of the external encoder.
+    /// The native unit is rotations per minute and values will be multiplied by
+    /// this conversion factor
+    ///
+    /// This is NOT RECOMMENDED, you will break uom outputs if you use this
+    #[builder(default = 1.0)]
+    pub velocity_factor: f64,
+
+    /// Set the sampling depth of the velocity calculation process of the
+    /// encoder. This value sets the number of samples in the average for
+    /// velocity readings.
+    ///
+    /// This value must be in the range [1, 64]. The default value is 64.
+    #[builder(default = 0, setter(transform = |depth: u8| {
+        assert!(depth.is_power_of_two() && depth <= 64);
+        depth
+    }))]
+    pub average_depth: u8,
+
+    /// Set the position measurement period used to calculate the velocity of
+    /// the encoder. This value must be >= 1ms and <= 100ms, and will be
+    /// interpreted in a whole number of ms. The default value is 100ms.
+    #[builder(default = 0, setter(transform = |d: Duration| d.as_millis() as u8))]
+    pub measurement_period: u8,
+}
+
 #[ffi_type(namespace = "ffi::device::spark::config")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FeedbackSensor {
     None = 0,
     RelativeEncoder = 1,
     AnalogSensor = 2,
+    /// The alternate encoder on a Spark Max, or the external encoder on a
+    /// Spark Flex
     AlternateEncoder = 3,
     AbsoluteEncoder = 4,
 }
@@ -229,9 +338,9 @@ pub struct RelativeEncoderConfig {
         self.inverted = inverted;
     }
 
-    /// Follow the output of another spark max
+    /// Follow the output of another spark max or spark flex
     #[mutator(requires = [inverted])]
-    fn follow_spark(&mut self, leader: &SparkMax, inverted: bool) {
+    fn follow_spark(&mut self, leader: &impl SparkBase, inverted: bool) {
         self.leader_id = leader.id();
         self.inverted = inverted;
     }
@@ -275,6 +384,17 @@ pub struct MotorConfig {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SparkMaxConfig {
     pub absolute_encoder: AbsoluteEncoderConfig,
+    pub alternate_encoder: AlternateEncoderConfig,
+    pub closed_loop: ClosedLoopConfig,
+    pub relative_encoder: RelativeEncoderConfig,
+    pub motor: MotorConfig,
+}
+
+#[ffi_type(namespace = "ffi::device::spark::config")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SparkFlexConfig {
+    pub absolute_encoder: AbsoluteEncoderConfig,
+    pub external_encoder: ExternalEncoderConfig,
     pub closed_loop: ClosedLoopConfig,
     pub relative_encoder: RelativeEncoderConfig,
     pub motor: MotorConfig,
@@ -285,6 +405,8 @@ pub struct SparkMaxConfig {
 pub enum MotorType {
     Brushed = 0,
     Brushless = 1,
+    /// The REV Vortex, which is brushless and driven by a Spark Flex
+    Vortex = 2,
 }
 
 #[ffi_type(namespace = "ffi::device::spark::config")]
@@ -302,12 +424,24 @@ impl Default for AbsoluteEncoderConfig {
     }
 }
 
+impl Default for AlternateEncoderConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
 impl Default for ClosedLoopConfig {
     fn default() -> Self {
         Self::builder().build()
     }
 }
 
+impl Default for ExternalEncoderConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
 impl Default for RelativeEncoderConfig {
     fn default() -> Self {
         Self::builder().build()
@@ -320,7 +454,10 @@ pub(super) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
         .register(extra_type!(FeedbackSensor))
         .register(extra_type!(MotorType))
         .register(extra_type!(AbsoluteEncoderConfig))
+        .register(extra_type!(AlternateEncoderConfig))
+        .register(extra_type!(ExternalEncoderConfig))
         .register(extra_type!(ClosedLoopConfig))
         .register(extra_type!(RelativeEncoderConfig))
         .register(extra_type!(SparkMaxConfig))
+        .register(extra_type!(SparkFlexConfig))
 }
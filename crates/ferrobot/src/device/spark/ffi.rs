@@ -1,9 +1,4 @@
-use std::{
-    ffi::{CStr, c_char, c_void},
-    mem,
-};
-
-use interoptopus::ffi::CStrPtr;
+use std::{ffi::c_void, mem, ptr};
 
 use super::prelude::*;
 
@@ -13,7 +8,10 @@ pub(crate) enum CommandType {
     SetPosition,
     SetVelocity,
     SetOutput,
+    SetEncoderPosition,
     Create,
+    CreateFlex,
+    Destroy,
 }
 
 #[ffi_type(namespace = "ffi::device::spark")]
@@ -45,10 +43,24 @@ impl Command {
         }
     }
 
-    pub(crate) fn create(config: spark::SparkMaxConfig) -> Self {
+    pub(crate) fn set_encoder_position(position: f64) -> Self {
+        Self {
+            kind: CommandType::SetEncoderPosition,
+            data: Box::into_raw(Box::new(position)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create(config: &spark::SparkMaxConfig) -> Self {
         Self {
             kind: CommandType::Create,
-            data: Box::into_raw(Box::new(config)) as *const c_void,
+            data: Box::into_raw(Box::new(*config)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create_flex(config: &spark::SparkFlexConfig) -> Self {
+        Self {
+            kind: CommandType::CreateFlex,
+            data: Box::into_raw(Box::new(*config)) as *const c_void,
         }
     }
 }
@@ -56,19 +68,34 @@ impl Command {
 impl Drop for Command {
     fn drop(&mut self) {
         match self.kind {
-            CommandType::SetVelocity | CommandType::SetPosition | CommandType::SetOutput => unsafe {
+            CommandType::SetVelocity
+            | CommandType::SetPosition
+            | CommandType::SetOutput
+            | CommandType::SetEncoderPosition => unsafe {
                 mem::drop(Box::from_raw(self.data as *mut f64));
             },
             CommandType::Create => unsafe {
                 mem::drop(Box::from_raw(self.data as *mut spark::SparkMaxConfig));
             },
+            CommandType::CreateFlex => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut spark::SparkFlexConfig));
+            },
+            CommandType::Destroy => {}
         }
     }
 }
 
+unsafe impl Send for Command {}
+
 impl device::Command for Command {
-    type Error = Error;
     type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
 }
 
 #[ffi_type(namespace = "ffi::device::spark")]
@@ -79,59 +106,11 @@ pub(crate) struct Data {
     pub(crate) position: f64,
     pub(crate) velocity: f64,
     pub(crate) current: f64,
-}
-
-#[allow(dead_code)]
-#[ffi_type(namespace = "ffi::device::spark")]
-#[derive(Clone, Copy, Debug, PartialEq, derive_more::Display)]
-pub enum ErrorType {
-    #[display("Tried to create existing motor")]
-    MotorExists,
-    #[display("Invalid motor configuration")]
-    BadConfig,
-    #[display("Invalid motor command")]
-    BadCommand,
-}
-
-#[allow(dead_code)]
-#[ffi_type(namespace = "ffi::device::spark")]
-#[derive(Debug, thiserror::Error)]
-#[error("{kind}: {}", message.as_str().unwrap_or("Unknown error"))]
-pub struct Error {
-    kind: ErrorType,
-    /// Heap-allocated string of which Rust has the responsibility of freeing
-    message: CStrPtr<'static>,
-}
-
-impl Clone for Error {
-    fn clone(&self) -> Self {
-        let c_str = self.message.as_c_str().unwrap();
-        let message = unsafe {
-            let ptr = libc::malloc(c_str.to_bytes().len() + 1).cast::<c_char>();
-            libc::strcpy(ptr, c_str.as_ptr());
-            CStr::from_ptr(ptr)
-        };
-
-        Self {
-            message: CStrPtr::from_cstr(message),
-            kind: self.kind,
-        }
-    }
-}
-
-impl Drop for Error {
-    fn drop(&mut self) {
-        let Some(c_str) = self.message.as_c_str() else {
-            return;
-        };
-
-        unsafe {
-            let ptr = c_str.as_ptr();
-            if !ptr.is_null() {
-                libc::free(ptr as *mut libc::c_void);
-            }
-        }
-    }
+    pub(crate) has_absolute: bool,
+    pub(crate) absolute_position: f64,
+    pub(crate) absolute_velocity: f64,
+    pub(crate) has_alternate: bool,
+    pub(crate) alternate_position: f64,
 }
 
 #[cfg(feature = "build")]
@@ -140,6 +119,4 @@ pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
         .register(extra_type!(CommandType))
         .register(extra_type!(Command))
         .register(extra_type!(Data))
-        .register(extra_type!(ErrorType))
-        .register(extra_type!(Error))
 }
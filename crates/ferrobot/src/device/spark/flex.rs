@@ -0,0 +1,43 @@
+use super::{Data, Error, SparkFlexConfig, prelude::*};
+
+#[derive(Debug)]
+pub struct SparkFlex {
+    can_id: u8,
+}
+
+impl SparkFlex {
+    pub async fn new(can_id: u8, config: SparkFlexConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { can_id });
+        let command = spark_ffi::Command::create_flex(&config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+}
+
+impl device::DeviceFFI for SparkFlex {
+    type CommandFFI = spark_ffi::Command;
+    type DataFFI = spark_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::SparkFlex;
+}
+
+impl device::Device for SparkFlex {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.can_id
+    }
+}
+
+impl Drop for SparkFlex {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+impl SparkBase for SparkFlex {}
@@ -1,50 +1,15 @@
 mod config;
 mod ffi;
+mod flex;
+mod max;
 pub mod prelude;
 
-use std::{backtrace::Backtrace, panic::Location};
-
 pub use config::*;
-pub use ffi::{Error as FFIError, ErrorType as FFIErrorType};
+pub use flex::SparkFlex;
+pub use max::SparkMax;
 use prelude::*;
-use thiserror::Error;
 use uom::si::{angle::revolution, angular_velocity::revolution_per_minute as rpm};
 
-#[allow(private_interfaces)]
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("Incorrect parameter for `set_output`: Expected -1.0 to 1.0, got {0}")]
-    InvalidOutput(f64),
-
-    #[error("At {location}: device store error: {source:?}")]
-    DeviceStore {
-        #[from]
-        source: device_ctx::Error,
-        location: &'static Location<'static>,
-        backtrace: Backtrace,
-    },
-
-    #[error("At {location}: FFI error: {source:?}")]
-    FFI {
-        #[from]
-        source: FFIError,
-        location: &'static Location<'static>,
-        backtrace: Backtrace,
-    },
-}
-
-impl From<*const FFIError> for Error {
-    #[allow(clippy::not_unsafe_ptr_arg_deref)]
-    #[track_caller]
-    fn from(value: *const FFIError) -> Self {
-        Self::FFI {
-            source: unsafe { &*value }.clone(),
-            location: Location::caller(),
-            backtrace: Backtrace::capture(),
-        }
-    }
-}
-
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Data {
     pub connected: bool,
@@ -52,6 +17,13 @@ pub struct Data {
     pub position: Angle,
     pub velocity: AngularVelocity,
     pub current: ElectricCurrent,
+    /// Position of the absolute encoder, if one is enabled
+    pub absolute_position: Option<Angle>,
+    /// Velocity of the absolute encoder, if one is enabled
+    pub absolute_velocity: Option<AngularVelocity>,
+    /// Position of the alternate (Spark Max) or external (Spark Flex) encoder,
+    /// if one is enabled
+    pub alternate_position: Option<Angle>,
 }
 
 impl<'a> From<&'a spark_ffi::Data> for Data {
@@ -62,81 +34,108 @@ impl<'a> From<&'a spark_ffi::Data> for Data {
             position: Angle::new::<revolution>(value.position),
             velocity: AngularVelocity::new::<rpm>(value.velocity),
             current: ElectricCurrent::new::<amp>(value.current),
+            absolute_position: value
+                .has_absolute
+                .then(|| Angle::new::<revolution>(value.absolute_position)),
+            absolute_velocity: value
+                .has_absolute
+                .then(|| AngularVelocity::new::<rpm>(value.absolute_velocity)),
+            alternate_position: value
+                .has_alternate
+                .then(|| Angle::new::<revolution>(value.alternate_position)),
         }
     }
 }
 
-#[derive(Debug)]
-pub struct SparkMax {
-    can_id: u8,
-}
-
-impl SparkMax {
-    pub async fn new(can_id: u8, config: SparkMaxConfig) -> Result<Arc<Self>, Error> {
-        let ctx = DeviceContext::instance();
-        let this = Arc::new(Self { can_id });
-        let command = spark_ffi::Command::create(config);
-
-        ctx.add_device(&this).await?;
-        ctx.command(&*this, command).await??;
-
-        Ok(this)
-    }
-
-    pub async fn data(&self) -> Option<Data> {
-        let ctx = DeviceContext::instance();
-        ctx.data(self).await
+impl Interpolate for Data {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            connected: self.connected.interpolate(&other.connected, t),
+            output: self.output.interpolate(&other.output, t),
+            position: self.position.interpolate(&other.position, t),
+            velocity: self.velocity.interpolate(&other.velocity, t),
+            current: self.current.interpolate(&other.current, t),
+            absolute_position: match (self.absolute_position, other.absolute_position) {
+                (Some(a), Some(b)) => Some(device::interpolate_wrapped(a, b, t)),
+                (a, b) => *device::nearest(&a, &b, t),
+            },
+            absolute_velocity: self
+                .absolute_velocity
+                .interpolate(&other.absolute_velocity, t),
+            alternate_position: self
+                .alternate_position
+                .interpolate(&other.alternate_position, t),
+        }
     }
+}
 
-    pub async fn set_position(&self, position: Angle) -> Result<(), Error> {
-        debug!("Setting spark {} position to {:?}", self.can_id, position);
+/// Functionality shared by all REV Spark motor controllers
+///
+/// Setters return once the robot has applied the command. Called from a
+/// periodic function or command, that's within the same tick. Called from
+/// anywhere else, the command waits for the next tick, up to a robot loop
+/// (20ms) later.
+#[allow(private_bounds)]
+pub trait SparkBase:
+    Device<Data = Data>
+    + device::DeviceFFI<CommandFFI = spark_ffi::Command, DataFFI = spark_ffi::Data>
+    + Sized
+{
+    async fn set_position(&self, position: Angle) -> Result<(), Error> {
+        debug!("Setting spark {} position to {:?}", self.id(), position);
 
         let position = position.get::<revolution>();
-        let ctx = DeviceContext::instance();
+        let ctx = DeviceContext::current();
         let command = spark_ffi::Command::set_position(position);
 
-        ctx.command(self, command).await??;
+        ctx.command(self, command).await?;
         Ok(())
     }
 
-    pub async fn set_velocity(&self, velocity: AngularVelocity) -> Result<(), Error> {
-        debug!("Setting spark {} velocity to {:?}", self.can_id, velocity);
+    async fn set_velocity(&self, velocity: AngularVelocity) -> Result<(), Error> {
+        debug!("Setting spark {} velocity to {:?}", self.id(), velocity);
 
         let velocity = velocity.get::<rpm>();
-        let ctx = DeviceContext::instance();
+        let ctx = DeviceContext::current();
         let command = spark_ffi::Command::set_velocity(velocity);
 
-        ctx.command(self, command).await??;
+        ctx.command(self, command).await?;
         Ok(())
     }
 
-    pub async fn set_output(&self, output: f64) -> Result<(), Error> {
+    async fn set_output(&self, output: f64) -> Result<(), Error> {
         if !(-1.0..=1.0).contains(&output) {
-            return Err(Error::InvalidOutput(output));
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!("Incorrect parameter for `set_output`: Expected -1.0 to 1.0, got {output}"),
+            )
+            .with_device(self.into()));
         }
 
-        debug!("Setting spark {} output to {}", self.can_id, output);
+        debug!("Setting spark {} output to {}", self.id(), output);
 
-        let ctx = DeviceContext::instance();
+        let ctx = DeviceContext::current();
         let command = spark_ffi::Command::set_output(output);
 
-        ctx.command(self, command).await??;
+        ctx.command(self, command).await?;
         Ok(())
     }
-}
-
-impl device::DeviceFFI for SparkMax {
-    type CommandFFI = spark_ffi::Command;
-    type DataFFI = spark_ffi::Data;
 
-    const TYPE: device_ffi::Type = device_ffi::Type::SparkMax;
-}
+    /// Set the position of the relative encoder, e.g. to zero a mechanism at a
+    /// limit switch or to seed it from the absolute encoder.
+    async fn set_encoder_position(&self, position: Angle) -> Result<(), Error> {
+        debug!(
+            "Setting spark {} encoder position to {:?}",
+            self.id(),
+            position
+        );
 
-impl device::Device for SparkMax {
-    type Data = Data;
+        let position = position.get::<revolution>();
+        let ctx = DeviceContext::current();
+        let command = spark_ffi::Command::set_encoder_position(position);
 
-    fn id(&self) -> u8 {
-        self.can_id
+        ctx.command(self, command).await?;
+        Ok(())
     }
 }
 
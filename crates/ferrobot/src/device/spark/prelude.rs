@@ -4,4 +4,4 @@ pub(crate) mod spark_ffi {
     pub(crate) use crate::device::spark::ffi::*;
 }
 
-pub use crate::device::spark::SparkMax;
+pub use crate::device::spark::{SparkBase, SparkFlex, SparkMax};
@@ -0,0 +1,41 @@
+use std::sync::LazyLock;
+
+use super::prelude::*;
+use crate::control::event::Event;
+
+/// Emitted when the robot hasn't received data from a device for longer than
+/// the stale threshold, e.g. because it dropped off the CAN bus. Emitted once
+/// each time a device goes stale.
+#[derive(Debug)]
+pub struct DeviceStale {
+    _private: (),
+}
+
+impl DeviceStale {
+    #[must_use]
+    pub fn event() -> Arc<Self> {
+        static INSTANCE: LazyLock<Arc<DeviceStale>> =
+            LazyLock::new(|| Arc::new(DeviceStale { _private: () }));
+
+        Arc::clone(&INSTANCE)
+    }
+}
+
+impl Event for DeviceStale {
+    type Data = Stale;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stale {
+    pub device: device::DeviceId,
+    /// FPGA time the device's data was last received
+    pub last_seen: Time,
+    /// FPGA time of the update that found the device stale
+    pub now: Time,
+}
+
+/// Set how long the robot can go without receiving a device's data before a
+/// [`DeviceStale`] event is emitted. Defaults to 100ms.
+pub fn set_stale_threshold(threshold: Time) {
+    DeviceContext::current().set_stale_threshold(threshold);
+}
@@ -0,0 +1,210 @@
+use typed_builder::TypedBuilder;
+use uom::si::{
+    angular_acceleration::degree_per_second_squared, angular_jerk::degree_per_second_cubed,
+    angular_velocity::revolution_per_second as rps,
+};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GravityType {
+    /// The gravity feedforward is constant, e.g. for an elevator
+    ElevatorStatic = 0,
+    /// The gravity feedforward is scaled by the cosine of the mechanism
+    /// position, e.g. for an arm
+    ArmCosine = 1,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+#[builder(mutators(
+    /// Set the PID gains for the closed loop controller.
+    fn pid(&mut self, p: f64, i: f64, d: f64) {
+        self.kp = p;
+        self.ki = i;
+        self.kd = d;
+    }
+))]
+pub struct SlotConfig {
+    /// The proportional gain, in volts per rotation of error
+    #[builder(via_mutators, default = 0.0)]
+    pub kp: f64,
+
+    /// The integral gain, in volts per rotation-second of error
+    #[builder(via_mutators, default = 0.0)]
+    pub ki: f64,
+
+    /// The derivative gain, in volts per rotation per second of error
+    #[builder(via_mutators, default = 0.0)]
+    pub kd: f64,
+
+    /// The static feedforward, in volts
+    #[builder(default = 0.0)]
+    pub ks: f64,
+
+    /// The velocity feedforward, in volts per rotation per second
+    #[builder(default = 0.0)]
+    pub kv: f64,
+
+    /// The acceleration feedforward, in volts per rotation per second squared
+    #[builder(default = 0.0)]
+    pub ka: f64,
+
+    /// The gravity feedforward, in volts
+    #[builder(default = 0.0)]
+    pub kg: f64,
+
+    /// How the gravity feedforward is applied
+    #[builder(default = GravityType::ElevatorStatic)]
+    pub gravity_type: GravityType,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct MotionMagicConfig {
+    /// The maximum velocity of a motion magic profile, in mechanism rotations
+    /// per second
+    #[builder(default = 0.0, setter(transform = |v: AngularVelocity| v.get::<rps>()))]
+    pub cruise_velocity: f64,
+
+    /// The acceleration of a motion magic profile, in mechanism rotations per
+    /// second squared
+    #[builder(default = 0.0, setter(transform = |a: AngularAcceleration| {
+        a.get::<degree_per_second_squared>() / 360.0
+    }))]
+    pub acceleration: f64,
+
+    /// The jerk of a motion magic profile, in mechanism rotations per second
+    /// cubed. Zero disables jerk limiting.
+    #[builder(default = 0.0, setter(transform = |j: AngularJerk| {
+        j.get::<degree_per_second_cubed>() / 360.0
+    }))]
+    pub jerk: f64,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+#[builder(mutators(
+    /// Limit the current through the motor windings
+    fn stator_limit(&mut self, current: ElectricCurrent) {
+        self.stator_limit = current.get::<amp>();
+        self.stator_limit_enable = true;
+    }
+
+    /// Limit the current drawn from the battery
+    fn supply_limit(&mut self, current: ElectricCurrent) {
+        self.supply_limit = current.get::<amp>();
+        self.supply_limit_enable = true;
+    }
+))]
+pub struct CurrentLimitsConfig {
+    /// The stator current limit, in amps
+    #[builder(via_mutators, default = 120.0)]
+    pub stator_limit: f64,
+
+    /// Whether the stator current limit is enabled
+    #[builder(via_mutators, default = true)]
+    pub stator_limit_enable: bool,
+
+    /// The supply current limit, in amps
+    #[builder(via_mutators, default = 70.0)]
+    pub supply_limit: f64,
+
+    /// Whether the supply current limit is enabled
+    #[builder(via_mutators, default = true)]
+    pub supply_limit_enable: bool,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NeutralMode {
+    /// The motor will coast when not powered
+    Coast = 0,
+    /// The motor will hold its position when not powered
+    Brake = 1,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, PartialEq, TypedBuilder)]
+pub struct TalonFXConfig {
+    /// Gains for closed loop slot 0
+    #[builder(default)]
+    pub slot0: SlotConfig,
+
+    /// Gains for closed loop slot 1
+    #[builder(default)]
+    pub slot1: SlotConfig,
+
+    /// Gains for closed loop slot 2
+    #[builder(default)]
+    pub slot2: SlotConfig,
+
+    /// Motion magic profile constraints
+    #[builder(default)]
+    pub motion_magic: MotionMagicConfig,
+
+    /// Stator and supply current limits
+    #[builder(default)]
+    pub current_limits: CurrentLimitsConfig,
+
+    /// The neutral mode of the motor
+    #[builder(default = NeutralMode::Brake)]
+    pub neutral_mode: NeutralMode,
+
+    /// Whether the motor is inverted, i.e. positive output is clockwise
+    /// when looking at the shaft
+    #[builder(default = false)]
+    pub inverted: bool,
+
+    /// The ratio of sensor rotations to mechanism rotations. All positions
+    /// and velocities are reported in mechanism rotations.
+    #[builder(default = 1.0)]
+    pub sensor_to_mechanism_ratio: f64,
+}
+
+/// A closed loop gain slot
+#[ffi_type(namespace = "ffi::device::talonfx::config")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Slot {
+    #[default]
+    Slot0 = 0,
+    Slot1 = 1,
+    Slot2 = 2,
+}
+
+impl Default for SlotConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Default for MotionMagicConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Default for CurrentLimitsConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Default for TalonFXConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(GravityType))
+        .register(extra_type!(NeutralMode))
+        .register(extra_type!(Slot))
+        .register(extra_type!(SlotConfig))
+        .register(extra_type!(MotionMagicConfig))
+        .register(extra_type!(CurrentLimitsConfig))
+        .register(extra_type!(TalonFXConfig))
+}
@@ -0,0 +1,137 @@
+use std::{ffi::c_void, mem, ptr};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::talonfx")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    SetDutyCycle,
+    SetVoltage,
+    SetVelocity,
+    SetPosition,
+    SetMotionMagic,
+    Create,
+    Destroy,
+}
+
+/// A closed loop setpoint along with the gain slot to use
+#[ffi_type(namespace = "ffi::device::talonfx")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Setpoint {
+    value: f64,
+    slot: talonfx::Slot,
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    pub(crate) fn set_duty_cycle(output: f64) -> Self {
+        Self {
+            kind: CommandType::SetDutyCycle,
+            data: Box::into_raw(Box::new(output)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_voltage(voltage: f64) -> Self {
+        Self {
+            kind: CommandType::SetVoltage,
+            data: Box::into_raw(Box::new(voltage)) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_velocity(velocity: f64, slot: talonfx::Slot) -> Self {
+        Self {
+            kind: CommandType::SetVelocity,
+            data: Box::into_raw(Box::new(Setpoint {
+                value: velocity,
+                slot,
+            })) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_position(position: f64, slot: talonfx::Slot) -> Self {
+        Self {
+            kind: CommandType::SetPosition,
+            data: Box::into_raw(Box::new(Setpoint {
+                value: position,
+                slot,
+            })) as *const c_void,
+        }
+    }
+
+    pub(crate) fn set_motion_magic(position: f64, slot: talonfx::Slot) -> Self {
+        Self {
+            kind: CommandType::SetMotionMagic,
+            data: Box::into_raw(Box::new(Setpoint {
+                value: position,
+                slot,
+            })) as *const c_void,
+        }
+    }
+
+    pub(crate) fn create(config: &talonfx::TalonFXConfig) -> Self {
+        Self {
+            kind: CommandType::Create,
+            data: Box::into_raw(Box::new(*config)) as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::SetDutyCycle | CommandType::SetVoltage => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut f64));
+            },
+            CommandType::SetVelocity | CommandType::SetPosition | CommandType::SetMotionMagic => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut Setpoint));
+            },
+            CommandType::Create => unsafe {
+                mem::drop(Box::from_raw(self.data as *mut talonfx::TalonFXConfig));
+            },
+            CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+#[ffi_type(namespace = "ffi::device::talonfx")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Data {
+    pub(crate) connected: bool,
+    pub(crate) position: f64,
+    pub(crate) velocity: f64,
+    pub(crate) acceleration: f64,
+    pub(crate) duty_cycle: f64,
+    pub(crate) motor_voltage: f64,
+    pub(crate) supply_voltage: f64,
+    pub(crate) stator_current: f64,
+    pub(crate) supply_current: f64,
+    pub(crate) temperature: f64,
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Setpoint))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
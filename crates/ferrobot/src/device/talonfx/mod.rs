@@ -0,0 +1,180 @@
+mod config;
+mod ffi;
+pub mod prelude;
+
+pub use config::*;
+use prelude::*;
+use uom::si::{
+    angle::revolution, angular_acceleration::degree_per_second_squared,
+    angular_velocity::revolution_per_second as rps, thermodynamic_temperature::degree_celsius,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Data {
+    pub connected: bool,
+    /// Position of the mechanism, after the sensor to mechanism ratio
+    pub position: Angle,
+    /// Velocity of the mechanism, after the sensor to mechanism ratio
+    pub velocity: AngularVelocity,
+    /// Acceleration of the mechanism, after the sensor to mechanism ratio
+    pub acceleration: AngularAcceleration,
+    /// Applied duty cycle, from -1.0 to 1.0
+    pub duty_cycle: f64,
+    pub motor_voltage: ElectricPotential,
+    pub supply_voltage: ElectricPotential,
+    pub stator_current: ElectricCurrent,
+    pub supply_current: ElectricCurrent,
+    pub temperature: ThermodynamicTemperature,
+}
+
+impl<'a> From<&'a talonfx_ffi::Data> for Data {
+    fn from(value: &'a talonfx_ffi::Data) -> Self {
+        Self {
+            connected: value.connected,
+            position: Angle::new::<revolution>(value.position),
+            velocity: AngularVelocity::new::<rps>(value.velocity),
+            acceleration: AngularAcceleration::new::<degree_per_second_squared>(
+                value.acceleration * 360.0,
+            ),
+            duty_cycle: value.duty_cycle,
+            motor_voltage: ElectricPotential::new::<volt>(value.motor_voltage),
+            supply_voltage: ElectricPotential::new::<volt>(value.supply_voltage),
+            stator_current: ElectricCurrent::new::<amp>(value.stator_current),
+            supply_current: ElectricCurrent::new::<amp>(value.supply_current),
+            temperature: ThermodynamicTemperature::new::<degree_celsius>(value.temperature),
+        }
+    }
+}
+
+impl Interpolate for Data {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            connected: self.connected.interpolate(&other.connected, t),
+            position: self.position.interpolate(&other.position, t),
+            velocity: self.velocity.interpolate(&other.velocity, t),
+            acceleration: self.acceleration.interpolate(&other.acceleration, t),
+            duty_cycle: self.duty_cycle.interpolate(&other.duty_cycle, t),
+            motor_voltage: self.motor_voltage.interpolate(&other.motor_voltage, t),
+            supply_voltage: self.supply_voltage.interpolate(&other.supply_voltage, t),
+            stator_current: self.stator_current.interpolate(&other.stator_current, t),
+            supply_current: self.supply_current.interpolate(&other.supply_current, t),
+            temperature: self.temperature.interpolate(&other.temperature, t),
+        }
+    }
+}
+
+/// A CTRE `TalonFX` motor controller, e.g. a Kraken X60 or Falcon 500
+#[derive(Debug)]
+pub struct TalonFX {
+    can_id: u8,
+}
+
+impl TalonFX {
+    pub async fn new(can_id: u8, config: TalonFXConfig) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self { can_id });
+        let command = talonfx_ffi::Command::create(&config);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    pub async fn set_duty_cycle(&self, output: f64) -> Result<(), Error> {
+        if !(-1.0..=1.0).contains(&output) {
+            return Err(Error::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Incorrect parameter for `set_duty_cycle`: Expected -1.0 to 1.0, got {output}"
+                ),
+            )
+            .with_device(self.into()));
+        }
+
+        debug!("Setting talonfx {} duty cycle to {}", self.can_id, output);
+
+        let ctx = DeviceContext::current();
+        let command = talonfx_ffi::Command::set_duty_cycle(output);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn set_voltage(&self, voltage: ElectricPotential) -> Result<(), Error> {
+        debug!("Setting talonfx {} voltage to {:?}", self.can_id, voltage);
+
+        let voltage = voltage.get::<volt>();
+        let ctx = DeviceContext::current();
+        let command = talonfx_ffi::Command::set_voltage(voltage);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn set_velocity(&self, velocity: AngularVelocity, slot: Slot) -> Result<(), Error> {
+        debug!("Setting talonfx {} velocity to {:?}", self.can_id, velocity);
+
+        let velocity = velocity.get::<rps>();
+        let ctx = DeviceContext::current();
+        let command = talonfx_ffi::Command::set_velocity(velocity, slot);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    pub async fn set_position(&self, position: Angle, slot: Slot) -> Result<(), Error> {
+        debug!("Setting talonfx {} position to {:?}", self.can_id, position);
+
+        let position = position.get::<revolution>();
+        let ctx = DeviceContext::current();
+        let command = talonfx_ffi::Command::set_position(position, slot);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+
+    /// Move to a position following the configured motion magic profile
+    pub async fn set_motion_magic(&self, position: Angle, slot: Slot) -> Result<(), Error> {
+        debug!(
+            "Setting talonfx {} motion magic target to {:?}",
+            self.can_id, position
+        );
+
+        let position = position.get::<revolution>();
+        let ctx = DeviceContext::current();
+        let command = talonfx_ffi::Command::set_motion_magic(position, slot);
+
+        ctx.command(self, command).await?;
+        Ok(())
+    }
+}
+
+impl device::DeviceFFI for TalonFX {
+    type CommandFFI = talonfx_ffi::Command;
+    type DataFFI = talonfx_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Can;
+    const TYPE: device_ffi::Type = device_ffi::Type::TalonFX;
+}
+
+impl device::Device for TalonFX {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.can_id
+    }
+}
+
+impl Drop for TalonFX {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(mut builder: InventoryBuilder) -> InventoryBuilder {
+    builder = ffi::__ffi_inventory(builder);
+    builder = config::__ffi_inventory(builder);
+    builder
+}
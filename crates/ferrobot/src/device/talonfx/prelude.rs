@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod talonfx_ffi {
+    pub(crate) use crate::device::talonfx::ffi::*;
+}
+
+pub use crate::device::talonfx::TalonFX;
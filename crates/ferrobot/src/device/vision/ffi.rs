@@ -0,0 +1,116 @@
+use std::{
+    ffi::{CString, c_char, c_void},
+    mem, ptr, slice,
+};
+
+use super::prelude::*;
+
+#[ffi_type(namespace = "ffi::device::vision")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CommandType {
+    Create,
+    Destroy,
+}
+
+#[ffi_type(namespace = "ffi::device::vision")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Command {
+    kind: CommandType,
+    data: *const c_void,
+}
+
+impl Command {
+    /// Subscribe to the camera's `NetworkTables` table
+    pub(crate) fn create(table: &str) -> Self {
+        let table = CString::new(table).unwrap_or_default();
+
+        Self {
+            kind: CommandType::Create,
+            data: table.into_raw() as *const c_void,
+        }
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        match self.kind {
+            CommandType::Create => unsafe {
+                mem::drop(CString::from_raw(self.data as *mut c_char));
+            },
+            CommandType::Destroy => {}
+        }
+    }
+}
+
+unsafe impl Send for Command {}
+
+impl device::Command for Command {
+    type Ok = ();
+
+    fn destroy() -> Self {
+        Self {
+            kind: CommandType::Destroy,
+            data: ptr::null(),
+        }
+    }
+}
+
+/// The raw `NetworkTables` entries of a camera. See [`vision::Camera`] for the
+/// layout of `targets` and `pose`.
+#[ffi_type(namespace = "ffi::device::vision")]
+#[derive(Debug)]
+pub(crate) struct Data {
+    pub(crate) connected: bool,
+    /// FPGA time the frame was captured, in seconds
+    pub(crate) timestamp: f64,
+    /// Malloc'd by the robot
+    targets: *const f64,
+    targets_len: usize,
+    /// Malloc'd by the robot
+    pose: *const f64,
+    pose_len: usize,
+}
+
+impl Data {
+    pub(crate) fn targets(&self) -> &[f64] {
+        if self.targets.is_null() {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(self.targets, self.targets_len) }
+    }
+
+    pub(crate) fn pose(&self) -> &[f64] {
+        if self.pose.is_null() {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(self.pose, self.pose_len) }
+    }
+}
+
+impl PartialEq for Data {
+    fn eq(&self, other: &Self) -> bool {
+        self.connected == other.connected
+            && self.timestamp == other.timestamp
+            && self.targets() == other.targets()
+            && self.pose() == other.pose()
+    }
+}
+
+impl Drop for Data {
+    fn drop(&mut self) {
+        unsafe {
+            libc::free(self.targets as *mut _);
+            libc::free(self.pose as *mut _);
+        }
+    }
+}
+
+#[cfg(feature = "build")]
+pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    builder
+        .register(extra_type!(CommandType))
+        .register(extra_type!(Command))
+        .register(extra_type!(Data))
+}
@@ -0,0 +1,52 @@
+use super::prelude::*;
+
+/// A position and orientation in 3d space, relative to the field origin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose3d {
+    pub x: Length,
+    pub y: Length,
+    pub z: Length,
+    pub roll: Angle,
+    pub pitch: Angle,
+    pub yaw: Angle,
+}
+
+/// A translation and rotation from one frame to another, e.g. from a camera
+/// to a target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform3d {
+    pub x: Length,
+    pub y: Length,
+    pub z: Length,
+    pub roll: Angle,
+    pub pitch: Angle,
+    pub yaw: Angle,
+}
+
+impl Pose3d {
+    /// Read `[x, y, z, roll, pitch, yaw]`, in meters and radians
+    pub(super) fn from_slice(values: &[f64]) -> Self {
+        Self {
+            x: Length::new::<meter>(values[0]),
+            y: Length::new::<meter>(values[1]),
+            z: Length::new::<meter>(values[2]),
+            roll: Angle::new::<radian>(values[3]),
+            pitch: Angle::new::<radian>(values[4]),
+            yaw: Angle::new::<radian>(values[5]),
+        }
+    }
+}
+
+impl Transform3d {
+    /// Read `[x, y, z, roll, pitch, yaw]`, in meters and radians
+    pub(super) fn from_slice(values: &[f64]) -> Self {
+        Self {
+            x: Length::new::<meter>(values[0]),
+            y: Length::new::<meter>(values[1]),
+            z: Length::new::<meter>(values[2]),
+            roll: Angle::new::<radian>(values[3]),
+            pitch: Angle::new::<radian>(values[4]),
+            yaw: Angle::new::<radian>(values[5]),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+mod ffi;
+mod geometry;
+pub mod prelude;
+mod replay;
+
+use std::{fs, path::Path};
+
+use async_lock::RwLock;
+pub use geometry::{Pose3d, Transform3d};
+use prelude::*;
+use uom::si::angle::degree;
+
+use crate::{control::event::Emitter, executor};
+
+/// Values per target in the `targets` array
+const TARGET_STRIDE: usize = 10;
+
+/// Values in the `pose` array, when a pose estimate is present
+const POSE_LEN: usize = 7;
+
+/// A target seen by a camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Target {
+    /// Horizontal angle from the camera's crosshair, positive right
+    pub yaw: Angle,
+    /// Vertical angle from the camera's crosshair, positive up
+    pub pitch: Angle,
+    /// Area of the target, as a percent of the image
+    pub area: f64,
+    /// The `AprilTag` id, if the target is a fiducial
+    pub fiducial_id: Option<u16>,
+    /// FPGA time the target was captured
+    pub timestamp: Time,
+    pub camera_to_target: Transform3d,
+}
+
+/// A robot pose estimated from every fiducial in view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoseEstimate {
+    pub pose: Pose3d,
+    /// FPGA time the estimate was captured
+    pub timestamp: Time,
+    /// The number of fiducials used for the estimate
+    pub tag_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Data {
+    pub connected: bool,
+    /// FPGA time the frame was captured
+    pub timestamp: Time,
+    pub targets: Vec<Target>,
+    pub pose_estimate: Option<PoseEstimate>,
+}
+
+impl Data {
+    /// Decode the `NetworkTables` arrays published by a camera. `targets`
+    /// holds [`TARGET_STRIDE`] values per target: `yaw, pitch` (degrees),
+    /// `area`, `fiducial id` (negative if none), then the camera to target
+    /// transform as `x, y, z` (meters) and `roll, pitch, yaw` (radians).
+    /// `pose` is either empty or the field relative robot pose as
+    /// `x, y, z, roll, pitch, yaw` followed by the tag count.
+    #[allow(clippy::cast_sign_loss)]
+    fn decode(
+        connected: bool,
+        timestamp: f64,
+        targets: &[f64],
+        pose: &[f64],
+    ) -> Result<Self, String> {
+        if !targets.len().is_multiple_of(TARGET_STRIDE) {
+            return Err(format!(
+                "targets length {} is not a multiple of {TARGET_STRIDE}",
+                targets.len()
+            ));
+        }
+
+        if !pose.is_empty() && pose.len() != POSE_LEN {
+            return Err(format!("pose length {} is not 0 or {POSE_LEN}", pose.len()));
+        }
+
+        let timestamp = Time::new::<second>(timestamp);
+
+        Ok(Self {
+            connected,
+            timestamp,
+            targets: targets
+                .chunks_exact(TARGET_STRIDE)
+                .map(|target| Target {
+                    yaw: Angle::new::<degree>(target[0]),
+                    pitch: Angle::new::<degree>(target[1]),
+                    area: target[2],
+                    fiducial_id: (target[3] >= 0.0).then_some(target[3] as u16),
+                    timestamp,
+                    camera_to_target: Transform3d::from_slice(&target[4..]),
+                })
+                .collect(),
+            pose_estimate: (!pose.is_empty()).then(|| PoseEstimate {
+                pose: Pose3d::from_slice(pose),
+                timestamp,
+                tag_count: pose[6] as usize,
+            }),
+        })
+    }
+
+    /// The target with the largest area, if any
+    #[must_use]
+    pub fn best_target(&self) -> Option<&Target> {
+        self.targets.iter().max_by(|a, b| a.area.total_cmp(&b.area))
+    }
+}
+
+impl<'a> From<&'a vision_ffi::Data> for Data {
+    fn from(value: &'a vision_ffi::Data) -> Self {
+        Self::decode(
+            value.connected,
+            value.timestamp,
+            value.targets(),
+            value.pose(),
+        )
+        .unwrap_or_else(|message| {
+            warn!("Dropping malformed vision frame: {message}");
+
+            Self {
+                connected: value.connected,
+                timestamp: Time::new::<second>(value.timestamp),
+                targets: Vec::new(),
+                pose_estimate: None,
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Backend {
+    /// Frames are published to `NetworkTables` by a coprocessor and supplied
+    /// by the robot each tick
+    NetworkTables,
+    /// Frames are played back from a file
+    Replay { current: RwLock<Option<Data>> },
+}
+
+/// A vision camera, e.g. running `PhotonVision` or a Limelight
+#[derive(Debug)]
+pub struct Camera {
+    id: u8,
+    name: String,
+    backend: Backend,
+}
+
+impl Camera {
+    /// A camera publishing to the `NetworkTables` table `name`. Each frame's
+    /// `targets`, `pose` and `latency` must be published with the same
+    /// timestamp, or the pose and latency are dropped from the frame.
+    pub async fn new(id: u8, name: impl Into<String>) -> Result<Arc<Self>, Error> {
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            id,
+            name: name.into(),
+            backend: Backend::NetworkTables,
+        });
+        let command = vision_ffi::Command::create(&this.name);
+
+        ctx.add_device(&this).await?;
+        ctx.command(&*this, command).await?;
+
+        Ok(this)
+    }
+
+    /// A camera playing back frames from a file, in real time. Events are
+    /// emitted for each frame, as they would be on the robot.
+    pub async fn replay(id: u8, path: impl AsRef<Path>) -> Result<Arc<Self>, Error> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|err| {
+            Error::new(ErrorKind::Io, format!("Failed to read replay file: {err}")).with_source(err)
+        })?;
+        let frames = replay::parse(&contents)?;
+
+        let ctx = DeviceContext::current();
+        let this = Arc::new(Self {
+            id,
+            name: path.display().to_string(),
+            backend: Backend::Replay {
+                current: RwLock::new(None),
+            },
+        });
+
+        ctx.add_device(&this).await?;
+
+        let camera = Arc::downgrade(&this);
+        Runtime::current().spawn(async move {
+            for (i, frame) in frames.iter().enumerate() {
+                if let Some(prev) = i.checked_sub(1).map(|prev| &frames[prev]) {
+                    executor::sleep(replay::delay(prev, frame)).await;
+                }
+
+                let Some(camera) = camera.upgrade() else {
+                    return;
+                };
+
+                if let Backend::Replay { current } = &camera.backend {
+                    *current.write().await = Some(frame.data.clone());
+                }
+
+                Emitter::current()
+                    .emit_device(camera, Arc::new(frame.data.clone()))
+                    .await;
+            }
+        });
+
+        Ok(this)
+    }
+
+    /// The `NetworkTables` table name, or the path of a replay file
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl device::DeviceFFI for Camera {
+    type CommandFFI = vision_ffi::Command;
+    type DataFFI = vision_ffi::Data;
+
+    const BUS: device_ffi::Bus = device_ffi::Bus::Network;
+    const TYPE: device_ffi::Type = device_ffi::Type::Camera;
+
+    fn destroy_command(&self) -> Option<Self::CommandFFI> {
+        match self.backend {
+            Backend::NetworkTables => Some(device::Command::destroy()),
+            // replayed cameras don't exist on the robot
+            Backend::Replay { .. } => None,
+        }
+    }
+}
+
+impl device::Device for Camera {
+    type Data = Data;
+
+    fn id(&self) -> u8 {
+        self.id
+    }
+
+    async fn data(&self) -> Option<Timestamped<Data>> {
+        match &self.backend {
+            Backend::NetworkTables => DeviceContext::current().data(self).await,
+            Backend::Replay { current } => {
+                let data = current.read().await.clone()?;
+                let timestamp = data.timestamp;
+
+                Some(Timestamped { data, timestamp })
+            }
+        }
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        device::release(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn replay_plays_back_every_frame_in_order() {
+        let path = env::temp_dir().join(format!("ferrobot-replay-{}.txt", process::id()));
+        fs::write(
+            &path,
+            "# timestamp | targets | pose\n\
+             1.0 | 5, -2, 1.5, 3, 1, 0, 0, 0, 0, 0 |\n\
+             1.2 | | 1, 2, 0, 0, 0, 0.5, 2\n",
+        )
+        .unwrap();
+
+        let runtime = Runtime::with_handler(Arc::new(|_| None));
+        let (first, last) = runtime.block_on(async {
+            let camera = Camera::replay(0, &path).await.unwrap();
+            executor::sleep(Duration::from_millis(50)).await;
+            let first = camera.data().await;
+            executor::sleep(Duration::from_millis(400)).await;
+
+            (first, camera.data().await)
+        });
+        fs::remove_file(&path).unwrap();
+
+        let first = first.unwrap().data;
+        assert_eq!(first.timestamp, Time::new::<second>(1.0));
+        assert_eq!(first.targets.len(), 1);
+        assert_eq!(first.targets[0].fiducial_id, Some(3));
+        assert_eq!(first.pose_estimate, None);
+
+        let last = last.unwrap().data;
+        assert_eq!(last.timestamp, Time::new::<second>(1.2));
+        assert!(last.targets.is_empty());
+        assert_eq!(last.pose_estimate.unwrap().tag_count, 2);
+    }
+}
+
+#[cfg(feature = "build")]
+pub(super) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
+    ffi::__ffi_inventory(builder)
+}
@@ -0,0 +1,7 @@
+pub use crate::device::prelude::*;
+
+pub(crate) mod vision_ffi {
+    pub(crate) use crate::device::vision::ffi::*;
+}
+
+pub use crate::device::vision::{Camera, Pose3d, Transform3d};
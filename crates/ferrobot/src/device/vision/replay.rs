@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use super::Data;
+use crate::{Error, ErrorKind};
+
+/// A frame read from a replay file
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Frame {
+    pub(super) timestamp: f64,
+    pub(super) data: Data,
+}
+
+/// Parse a replay file. Each line is one frame, in the form
+/// `timestamp | targets | pose`, where `targets` and `pose` are comma
+/// separated and laid out as they are in `NetworkTables`. Either may be
+/// empty. Blank lines and lines starting with `#` are skipped.
+pub(super) fn parse(contents: &str) -> Result<Vec<Frame>, Error> {
+    let mut frames = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let bad_line = |message: &str| {
+            Error::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid replay file, line {}: {message}", i + 1),
+            )
+        };
+
+        let mut fields = line.split('|');
+        let (Some(timestamp), Some(targets), Some(pose), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(bad_line("expected `timestamp | targets | pose`"));
+        };
+
+        let timestamp = timestamp
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| bad_line("invalid timestamp"))?;
+        let targets = parse_array(targets).ok_or_else(|| bad_line("invalid targets"))?;
+        let pose = parse_array(pose).ok_or_else(|| bad_line("invalid pose"))?;
+        let data =
+            Data::decode(true, timestamp, &targets, &pose).map_err(|message| bad_line(&message))?;
+
+        frames.push(Frame { timestamp, data });
+    }
+
+    Ok(frames)
+}
+
+fn parse_array(field: &str) -> Option<Vec<f64>> {
+    let field = field.trim();
+
+    if field.is_empty() {
+        return Some(Vec::new());
+    }
+
+    field
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+/// The delay between two frames, for playing them back in real time
+pub(super) fn delay(from: &Frame, to: &Frame) -> Duration {
+    Duration::from_secs_f64((to.timestamp - from.timestamp).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_parsed_skipping_blank_lines_and_comments() {
+        let frames =
+            parse("# a comment\n\n0.5 | | \n0.75 | 1, 2, 3, -1, 0, 0, 0, 0, 0, 0 |\n").unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].data.targets.is_empty());
+        assert_eq!(frames[1].data.targets[0].fiducial_id, None);
+        assert_eq!(delay(&frames[0], &frames[1]), Duration::from_millis(250));
+        assert_eq!(delay(&frames[1], &frames[0]), Duration::ZERO);
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_their_line_number() {
+        for contents in ["0.5 |", "0.5 | 1, 2 |", "x | |", "0.5 | | 1, a"] {
+            let err = parse(&format!("# header\n{contents}\n")).unwrap_err();
+
+            assert!(err.to_string().contains("line 2"), "{contents}: {err}");
+        }
+    }
+}
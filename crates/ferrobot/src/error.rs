@@ -0,0 +1,190 @@
+use std::{
+    backtrace::Backtrace,
+    error::{Error as StdError, Request},
+    fmt, io,
+    panic::Location,
+};
+
+use crate::device::{DeviceId, FFIError, FFIErrorType};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// What went wrong
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum ErrorKind {
+    #[display("Timed out")]
+    Timeout,
+    #[display("CAN fault")]
+    CanFault,
+    #[display("Device not found")]
+    NotFound,
+    #[display("Device already exists")]
+    AlreadyExists,
+    #[display("Invalid device configuration")]
+    BadConfig,
+    /// The device rejected a command it supports
+    #[display("Invalid device command")]
+    BadCommand,
+    #[display("Command not supported by device")]
+    UnsupportedCommand,
+    /// A HAL or vendor status code
+    #[display("HAL error {_0}")]
+    Hal(i32),
+    #[display("Invalid argument")]
+    InvalidArgument,
+    /// The command was dropped before the robot handled it
+    #[display("Command dropped")]
+    CommandDropped,
+    #[display("I/O error")]
+    Io,
+}
+
+impl ErrorKind {
+    /// Whether the error may go away if the command is retried, e.g. a
+    /// message lost to a noisy CAN bus
+    #[must_use]
+    pub fn is_transient(self) -> bool {
+        matches!(self, Self::Timeout | Self::CanFault)
+    }
+}
+
+/// An error from ferrobot, along with the device it concerns, if any
+#[derive(Debug)]
+pub struct Error(Box<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    kind: ErrorKind,
+    device: Option<DeviceId>,
+    message: String,
+    rev_status: Option<i32>,
+    source: Option<Box<dyn StdError + Send + Sync>>,
+    location: &'static Location<'static>,
+    backtrace: Backtrace,
+}
+
+impl Error {
+    #[track_caller]
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self(Box::new(Inner {
+            kind,
+            device: None,
+            message: message.into(),
+            rev_status: None,
+            source: None,
+            location: Location::caller(),
+            backtrace: Backtrace::capture(),
+        }))
+    }
+
+    #[must_use]
+    pub fn with_device(mut self, device: DeviceId) -> Self {
+        self.0.device = Some(device);
+        self
+    }
+
+    /// Report the error as raised at `location`, e.g. where a failed command
+    /// was sent rather than where its response was decoded
+    #[must_use]
+    pub(crate) fn at(mut self, location: &'static Location<'static>) -> Self {
+        self.0.location = location;
+        self
+    }
+
+    #[must_use]
+    pub fn with_source(mut self, source: impl StdError + Send + Sync + 'static) -> Self {
+        self.0.source = Some(Box::new(source));
+        self
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind
+    }
+
+    /// The device the error concerns
+    #[must_use]
+    pub fn device(&self) -> Option<DeviceId> {
+        self.0.device
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    /// The `REVLibError` code, if the error came from `REVLib`
+    #[must_use]
+    pub fn rev_error(&self) -> Option<i32> {
+        self.0.rev_status
+    }
+
+    /// Where the error was raised. For a failed device command, this is the
+    /// device method that sent it.
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.0.location
+    }
+
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.0.backtrace
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Inner {
+            kind,
+            device,
+            message,
+            location,
+            ..
+        } = &*self.0;
+
+        write!(f, "At {location}: {kind}")?;
+        if let Some(device) = device {
+            write!(f, " ({device})")?;
+        }
+        write!(f, ": {message}")
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0
+            .source
+            .as_deref()
+            .map(|source| source as &(dyn StdError + 'static))
+    }
+
+    fn provide<'a>(&'a self, request: &mut Request<'a>) {
+        request.provide_ref::<Backtrace>(&self.0.backtrace);
+    }
+}
+
+impl From<io::Error> for Error {
+    #[track_caller]
+    fn from(value: io::Error) -> Self {
+        Self::new(ErrorKind::Io, value.to_string()).with_source(value)
+    }
+}
+
+impl From<FFIError> for Error {
+    #[track_caller]
+    fn from(value: FFIError) -> Self {
+        let kind = match value.kind {
+            FFIErrorType::DeviceExists => ErrorKind::AlreadyExists,
+            FFIErrorType::DeviceNotFound => ErrorKind::NotFound,
+            FFIErrorType::BadConfig => ErrorKind::BadConfig,
+            FFIErrorType::BadCommand => ErrorKind::BadCommand,
+            FFIErrorType::UnsupportedCommand => ErrorKind::UnsupportedCommand,
+            FFIErrorType::Timeout => ErrorKind::Timeout,
+            FFIErrorType::CanFault => ErrorKind::CanFault,
+            FFIErrorType::Hal => ErrorKind::Hal(value.code),
+        };
+
+        let mut error = Self::new(kind, value.message.as_str().unwrap_or("Unknown error"));
+        error.0.rev_status = (value.rev_status != 0).then_some(value.rev_status);
+        error
+    }
+}
@@ -0,0 +1,24 @@
+use std::{pin::Pin, time::Duration};
+
+use ::async_std::task;
+use futures::future::BoxFuture;
+
+use super::Executor;
+
+/// async-std's global thread pool
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStd;
+
+impl Executor for AsyncStd {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        task::spawn(future);
+    }
+
+    fn block_on(&self, future: Pin<&mut (dyn Future<Output = ()> + '_)>) {
+        task::block_on(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(task::sleep(duration))
+    }
+}
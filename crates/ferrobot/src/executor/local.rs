@@ -0,0 +1,508 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, VecDeque},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        Condvar, Mutex, MutexGuard, PoisonError, Weak,
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+    },
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use futures::{future::BoxFuture, task::AtomicWaker};
+use typed_builder::TypedBuilder;
+
+use super::Executor;
+use crate::prelude::*;
+
+/// Where the thread blocking on a [`Local`] executor runs
+#[derive(Clone, Copy, Debug, TypedBuilder)]
+pub struct LocalConfig {
+    /// The core to pin the thread to. Defaults to core 1 on the roboRIO, so
+    /// the robot code doesn't share a core with the rest of the system.
+    #[builder(default = if cfg!(target_arch = "arm") { Some(1) } else { None })]
+    pub core: Option<usize>,
+
+    /// The `SCHED_FIFO` real-time priority of the thread, from 1 to 99.
+    /// Defaults to 15 on the roboRIO.
+    #[builder(default = if cfg!(target_arch = "arm") { Some(15) } else { None })]
+    pub priority: Option<i32>,
+}
+
+impl Default for LocalConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A single-threaded, deterministic executor. Tasks run only on the thread
+/// blocked in [`Executor::block_on`], one at a time and in the order they were
+/// woken, and timers fire in deadline order.
+///
+/// Once the runtime has ticked, timers follow robot time: they fire when a
+/// tick's timestamp passes their deadline, before anything in the tick runs,
+/// so the same ticks always run tasks in the same order. Until then, e.g. while
+/// the robot starts up, they follow the wall clock.
+///
+/// Tasks can be spawned from any thread, but don't run until a thread blocks
+/// on the executor.
+pub struct Local {
+    config: LocalConfig,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    wakeup: Condvar,
+    /// When the executor was created, which the wall clock counts from
+    epoch: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    ready: VecDeque<Arc<Task>>,
+    timers: BinaryHeap<Reverse<Timer>>,
+    next_timer: u64,
+    /// FPGA time of the latest tick, or `None` if the runtime hasn't ticked
+    robot_time: Option<Duration>,
+    /// Whether the future passed to `block_on` was woken
+    main_woken: bool,
+}
+
+struct Timer {
+    /// When the timer fires, on the executor's clock
+    deadline: Duration,
+    /// Breaks ties between timers with the same deadline, first come first
+    /// served
+    sequence: u64,
+    waker: Arc<AtomicWaker>,
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Timer {}
+
+impl PartialOrd for Timer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.deadline, self.sequence).cmp(&(other.deadline, other.sequence))
+    }
+}
+
+struct Task {
+    future: Mutex<Option<BoxFuture<'static, ()>>>,
+    shared: Weak<Shared>,
+    queued: AtomicBool,
+}
+
+impl Task {
+    fn run(self: Arc<Self>) {
+        self.queued.store(false, AtomicOrdering::Release);
+
+        let waker = Waker::from(Arc::clone(&self));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = self.future.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(inner) = future.as_mut() else {
+            return;
+        };
+
+        // a panicking task is dropped, rather than taking down every other
+        // task on the thread
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(&mut cx))) {
+            Ok(Poll::Pending) => {}
+            Ok(Poll::Ready(())) => *future = None,
+            Err(_) => {
+                error!("A task panicked, and was dropped");
+                *future = None;
+            }
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        if self.queued.swap(true, AtomicOrdering::AcqRel) {
+            return;
+        }
+
+        if let Some(shared) = self.shared.upgrade() {
+            shared.lock().ready.push_back(Arc::clone(&self));
+            shared.wakeup.notify_one();
+        }
+    }
+}
+
+struct MainWaker {
+    shared: Weak<Shared>,
+}
+
+impl Wake for MainWaker {
+    fn wake(self: Arc<Self>) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.lock().main_woken = true;
+            shared.wakeup.notify_one();
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The time on the executor's clock: robot time once the runtime has
+    /// ticked, or the wall clock before then
+    fn now(&self, state: &State) -> Duration {
+        state.robot_time.unwrap_or_else(|| self.epoch.elapsed())
+    }
+}
+
+impl State {
+    /// Take the wakers of the timers due at `now`, in deadline order
+    fn due(&mut self, now: Duration) -> Vec<Arc<AtomicWaker>> {
+        let mut due = Vec::new();
+
+        while let Some(Reverse(timer)) = self.timers.peek()
+            && timer.deadline <= now
+        {
+            let Some(Reverse(timer)) = self.timers.pop() else {
+                break;
+            };
+            due.push(timer.waker);
+        }
+
+        due
+    }
+}
+
+impl Local {
+    #[must_use]
+    pub fn new(config: LocalConfig) -> Self {
+        Self {
+            config,
+            shared: Arc::new(Shared {
+                state: Mutex::new(State::default()),
+                wakeup: Condvar::new(),
+                epoch: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wake the tasks whose timers are due, and wait until there is something
+    /// to run
+    fn park(&self) {
+        let mut state = self.shared.lock();
+        let now = self.shared.now(&state);
+        let due = state.due(now);
+
+        if !due.is_empty() {
+            // waking locks the state
+            drop(state);
+            for waker in due {
+                waker.wake();
+            }
+            return;
+        }
+
+        if !state.ready.is_empty() || state.main_woken {
+            return;
+        }
+
+        // on robot time, timers only fire when the runtime ticks
+        let _state = match state.timers.peek() {
+            Some(Reverse(timer)) if state.robot_time.is_none() => {
+                let timeout = timer.deadline.saturating_sub(now);
+                self.shared
+                    .wakeup
+                    .wait_timeout(state, timeout)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            _ => self
+                .shared
+                .wakeup
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+    }
+}
+
+impl Executor for Local {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        let task = Arc::new(Task {
+            future: Mutex::new(Some(future)),
+            shared: Arc::downgrade(&self.shared),
+            queued: AtomicBool::new(false),
+        });
+
+        task.wake();
+    }
+
+    fn block_on(&self, mut future: Pin<&mut (dyn Future<Output = ()> + '_)>) {
+        configure_thread(&self.config);
+
+        let waker = Waker::from(Arc::new(MainWaker {
+            shared: Arc::downgrade(&self.shared),
+        }));
+        let mut cx = Context::from_waker(&waker);
+
+        self.shared.lock().main_woken = true;
+
+        loop {
+            if std::mem::take(&mut self.shared.lock().main_woken)
+                && future.as_mut().poll(&mut cx).is_ready()
+            {
+                return;
+            }
+
+            // run everything that's ready, including tasks woken along the way
+            loop {
+                let task = self.shared.lock().ready.pop_front();
+                let Some(task) = task else {
+                    break;
+                };
+
+                task.run();
+            }
+
+            self.park();
+        }
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let now = self.shared.now(&self.shared.lock());
+
+        Box::pin(Sleep {
+            shared: Arc::clone(&self.shared),
+            deadline: now + duration,
+            waker: Arc::new(AtomicWaker::new()),
+            registered: false,
+        })
+    }
+
+    fn advance(&self, timestamp: u64) {
+        let mut state = self.shared.lock();
+        let time = Duration::from_micros(timestamp).max(state.robot_time.unwrap_or_default());
+
+        // timers set before the first tick keep the time they had left
+        if state.robot_time.is_none() {
+            let wall = self.shared.epoch.elapsed();
+            state.timers = std::mem::take(&mut state.timers)
+                .into_iter()
+                .map(|Reverse(timer)| {
+                    Reverse(Timer {
+                        deadline: time + timer.deadline.saturating_sub(wall),
+                        ..timer
+                    })
+                })
+                .collect();
+        }
+
+        state.robot_time = Some(time);
+        let due = state.due(time);
+        drop(state);
+
+        for waker in due {
+            waker.wake();
+        }
+        self.shared.wakeup.notify_one();
+    }
+}
+
+struct Sleep {
+    shared: Arc<Shared>,
+    /// When the sleep is over, on the executor's clock
+    deadline: Duration,
+    /// Shared with the timer, so polling again only updates the waker
+    waker: Arc<AtomicWaker>,
+    registered: bool,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let mut state = this.shared.lock();
+
+        if this.shared.now(&state) >= this.deadline {
+            return Poll::Ready(());
+        }
+
+        this.waker.register(cx.waker());
+
+        if !this.registered {
+            let sequence = state.next_timer;
+            state.next_timer += 1;
+            state.timers.push(Reverse(Timer {
+                deadline: this.deadline,
+                sequence,
+                waker: Arc::clone(&this.waker),
+            }));
+            drop(state);
+
+            this.registered = true;
+            this.shared.wakeup.notify_one();
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Pin the calling thread to a core and give it a real-time priority
+#[cfg(target_os = "linux")]
+fn configure_thread(config: &LocalConfig) {
+    if let Some(core) = config.core {
+        let result = unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_SET(core, &mut set);
+            libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &raw const set)
+        };
+
+        if result != 0 {
+            warn!(
+                "Failed to pin executor to core {core}: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    if let Some(priority) = config.priority {
+        let param = libc::sched_param {
+            sched_priority: priority,
+        };
+        let result = unsafe {
+            libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &raw const param)
+        };
+
+        if result != 0 {
+            warn!(
+                "Failed to set executor priority to {priority}: {}",
+                std::io::Error::from_raw_os_error(result)
+            );
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn configure_thread(_config: &LocalConfig) {}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use futures::future;
+
+    use super::*;
+
+    fn local() -> Local {
+        Local::new(LocalConfig::builder().core(None).priority(None).build())
+    }
+
+    /// Spawn a task that records `id` once it has slept for `delay`
+    fn record(executor: &Local, order: &Arc<Mutex<Vec<u32>>>, id: u32, delay: Option<Duration>) {
+        let order = Arc::clone(order);
+        let sleep = delay.map(|delay| executor.sleep(delay));
+
+        executor.spawn(Box::pin(async move {
+            if let Some(sleep) = sleep {
+                sleep.await;
+            }
+
+            order.lock().unwrap().push(id);
+        }));
+    }
+
+    #[test]
+    fn tasks_run_in_the_order_they_were_spawned() {
+        let executor = local();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for id in 0..3 {
+            record(&executor, &order, id, None);
+        }
+
+        executor.block_on(pin!(executor.sleep(Duration::from_millis(10))));
+        assert_eq!(*order.lock().unwrap(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn timers_fire_in_deadline_order() {
+        let executor = local();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        record(&executor, &order, 0, Some(Duration::from_millis(30)));
+        record(&executor, &order, 1, Some(Duration::from_millis(10)));
+        record(&executor, &order, 2, Some(Duration::from_millis(20)));
+        // same deadline as the first, so fires after it
+        record(&executor, &order, 3, Some(Duration::from_millis(30)));
+
+        executor.block_on(pin!(executor.sleep(Duration::from_millis(50))));
+        assert_eq!(*order.lock().unwrap(), [1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn timers_follow_robot_time_once_ticked() {
+        let executor = local();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        executor.advance(1_000_000);
+        record(&executor, &order, 0, Some(Duration::from_secs(30)));
+        record(&executor, &order, 1, Some(Duration::from_secs(10)));
+        record(&executor, &order, 2, Some(Duration::from_secs(20)));
+
+        // ticks 10s of robot time apart, without waiting on the wall clock
+        let mut ticks = (1..=3).map(|tick| 1_000_000 + tick * 10_000_000);
+        let mut recorded = Vec::new();
+        executor.block_on(pin!(future::poll_fn(|cx| {
+            recorded.push(order.lock().unwrap().clone());
+
+            let Some(timestamp) = ticks.next() else {
+                return Poll::Ready(());
+            };
+
+            executor.advance(timestamp);
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })));
+
+        assert_eq!(recorded, [vec![], vec![1], vec![1, 2], vec![1, 2, 0]]);
+    }
+
+    #[test]
+    fn polling_a_sleep_again_registers_one_timer() {
+        let executor = local();
+        let mut sleep = executor.sleep(Duration::from_secs(10));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        for _ in 0..3 {
+            assert!(sleep.as_mut().poll(&mut cx).is_pending());
+        }
+
+        assert_eq!(executor.shared.lock().timers.len(), 1);
+    }
+
+    #[test]
+    fn panicking_task_is_dropped() {
+        let executor = local();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        executor.spawn(Box::pin(async { panic!("task panics") }));
+        record(&executor, &order, 0, None);
+
+        executor.block_on(pin!(executor.sleep(Duration::from_millis(10))));
+        assert_eq!(*order.lock().unwrap(), [0]);
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "async-std")] mod async_std;
+#[cfg(feature = "local")] mod local;
+#[cfg(feature = "tokio")] mod tokio;
+
+use std::{
+    pin::{Pin, pin},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{
+    channel::oneshot,
+    future::{self, AbortHandle, Abortable, Aborted, BoxFuture, Either},
+};
+
+#[cfg(feature = "async-std")]
+pub use self::async_std::AsyncStd;
+#[cfg(feature = "local")]
+pub use self::local::{Local, LocalConfig};
+#[cfg(feature = "tokio")]
+pub use self::tokio::Tokio;
+use crate::{Runtime, prelude::*};
+
+#[cfg(not(any(feature = "async-std", feature = "tokio", feature = "local")))]
+compile_error!("Enable one of the `async-std`, `tokio` or `local` features to pick an executor");
+
+/// Runs the tasks of a [`Runtime`]
+pub trait Executor: Send + Sync + 'static {
+    /// Run `future` to completion in the background
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Block the calling thread until `future` completes
+    fn block_on(&self, future: Pin<&mut (dyn Future<Output = ()> + '_)>);
+
+    /// A future that completes after `duration`
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Called with the FPGA time of each tick, in microseconds, before
+    /// anything in the tick runs. Executors whose timers follow robot time
+    /// advance them here.
+    fn advance(&self, timestamp: u64) {
+        let _ = timestamp;
+    }
+}
+
+/// The executor picked by the enabled features. If several are enabled,
+/// `local` is preferred over `tokio`, and `tokio` over `async-std`.
+pub(crate) fn default() -> Arc<dyn Executor> {
+    #[cfg(feature = "local")]
+    let executor = Arc::new(Local::new(LocalConfig::default()));
+    #[cfg(all(feature = "tokio", not(feature = "local")))]
+    let executor = Arc::new(Tokio::new());
+    #[cfg(all(feature = "async-std", not(any(feature = "tokio", feature = "local"))))]
+    let executor = Arc::new(AsyncStd);
+
+    executor
+}
+
+/// A handle to a spawned task, which can be awaited for its output. Dropping
+/// the handle detaches the task.
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    abort: AbortHandle,
+    output: oneshot::Receiver<Result<T, Aborted>>,
+}
+
+impl<T: Send + 'static> JoinHandle<T> {
+    /// Wrap `future` so it can be cancelled, and its output sent to the handle
+    pub(crate) fn new<F>(future: F) -> (Self, impl Future<Output = ()> + Send + 'static)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let (abort, registration) = AbortHandle::new_pair();
+        let (sender, output) = oneshot::channel();
+
+        let task = async move {
+            let output = Abortable::new(future, registration).await;
+
+            // the handle may have been dropped
+            let _ = sender.send(output);
+        };
+
+        (Self { abort, output }, task)
+    }
+
+    /// Stop the task at its next await point, and wait for it to stop. Returns
+    /// the output if the task finished first.
+    pub async fn cancel(self) -> Option<T> {
+        self.abort.abort();
+        self.output.await.ok()?.ok()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        Pin::new(&mut self.output)
+            .poll(cx)
+            .map(|output| output.expect("Task panicked").expect("Task was cancelled"))
+    }
+}
+
+/// Wait for `duration` on the current runtime's executor
+pub async fn sleep(duration: Duration) {
+    Runtime::current().sleep(duration).await;
+}
+
+/// Run `future`, giving up if it doesn't complete within `duration`
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    let future = pin!(future);
+    let sleep = pin!(sleep(duration));
+
+    match future::select(future, sleep).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,46 @@
+use std::{pin::Pin, time::Duration};
+
+use ::tokio::runtime;
+use futures::future::BoxFuture;
+
+use super::Executor;
+
+/// A multi-threaded tokio runtime
+pub struct Tokio {
+    runtime: runtime::Runtime,
+}
+
+impl Tokio {
+    #[must_use]
+    pub fn new() -> Self {
+        let runtime = runtime::Builder::new_multi_thread()
+            .enable_time()
+            .thread_name("ferrobot")
+            .build()
+            .expect("Failed to start tokio runtime");
+
+        Self { runtime }
+    }
+}
+
+impl Default for Tokio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Executor for Tokio {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        self.runtime.spawn(future);
+    }
+
+    fn block_on(&self, future: Pin<&mut (dyn Future<Output = ()> + '_)>) {
+        self.runtime.block_on(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        // the timer has to be created inside the runtime
+        let _guard = self.runtime.enter();
+        Box::pin(::tokio::time::sleep(duration))
+    }
+}
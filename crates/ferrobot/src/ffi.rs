@@ -1,7 +1,38 @@
-use std::{ffi::c_void, slice};
+use std::{ffi::c_void, fmt, mem::ManuallyDrop, ptr, slice};
 
 use crate::device::prelude::*;
 
+include!(concat!(env!("OUT_DIR"), "/abi.rs"));
+
+/// Bumped when the contract between Rust and the robot changes in a way the
+/// inventory hash can't see, e.g. who frees a pointer
+pub(crate) const ABI_VERSION: u32 = 1;
+
+/// Identifies the bindings one side of the FFI was built against. The robot
+/// passes the values from its generated header to `start_thread`, which
+/// refuses to run if they differ from the library's.
+#[ffi_type(namespace = "ffi")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct AbiVersion {
+    pub(crate) version: u32,
+    /// Hash of the ffi types and function signatures the interoptopus
+    /// inventory is generated from, ignoring docs and formatting
+    pub(crate) inventory_hash: u64,
+}
+
+impl AbiVersion {
+    pub(crate) const CURRENT: Self = Self {
+        version: ABI_VERSION,
+        inventory_hash: INVENTORY_HASH,
+    };
+}
+
+impl fmt::Display for AbiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{} ({:#018x})", self.version, self.inventory_hash)
+    }
+}
+
 #[ffi_type(namespace = "ffi")]
 pub(crate) struct DeviceDatas {
     data: *const device_ffi::Data,
@@ -9,6 +40,20 @@ pub(crate) struct DeviceDatas {
 }
 
 impl DeviceDatas {
+    /// Datas laid out as the robot supplies them
+    #[cfg(test)]
+    pub(crate) fn from_vec(datas: Vec<device_ffi::Data>) -> Self {
+        let len = datas.len();
+        let data = unsafe { libc::malloc(size_of::<device_ffi::Data>() * len.max(1)) }
+            .cast::<device_ffi::Data>();
+
+        for (idx, device_data) in datas.into_iter().enumerate() {
+            unsafe { data.add(idx).write(device_data) };
+        }
+
+        Self { data, len }
+    }
+
     #[allow(clippy::mut_from_ref)]
     fn as_slice(&self) -> &mut [device_ffi::Data] {
         unsafe { slice::from_raw_parts_mut(self.data.cast_mut(), self.len) }
@@ -31,15 +76,70 @@ impl Drop for DeviceDatas {
 unsafe impl Send for DeviceDatas {}
 unsafe impl Sync for DeviceDatas {}
 
+/// CAN bus health, as reported by the roboRIO
+#[ffi_type(namespace = "ffi")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CanStatus {
+    /// Fraction of the bus bandwidth in use, from 0.0 to 1.0
+    pub(crate) utilization: f32,
+    pub(crate) bus_off_count: u32,
+    pub(crate) tx_full_count: u32,
+    pub(crate) rx_error_count: u32,
+    pub(crate) tx_error_count: u32,
+}
+
 #[ffi_type(namespace = "ffi")]
 pub(crate) struct FFIData {
     pub(crate) devices: DeviceDatas,
+    /// FPGA time the data was collected, in microseconds
+    pub(crate) timestamp: u64,
+    pub(crate) can: CanStatus,
 }
 
+/// The robot's response to a command. `data` is malloc'd by the robot, and
+/// is either the command's output or a `device::Error`.
 #[ffi_type(namespace = "ffi")]
 pub(crate) struct Response {
-    pub(crate) ok: bool,
-    pub(crate) data: *const c_void,
+    ok: bool,
+    data: *const c_void,
+}
+
+impl Response {
+    /// A successful response with no output, as the robot would send
+    #[cfg(test)]
+    pub(crate) fn ok() -> Self {
+        Self {
+            ok: true,
+            data: ptr::null(),
+        }
+    }
+
+    /// Take ownership of the output, or the error if the command failed
+    pub(crate) fn decode<T: device::Decode>(self) -> Result<T, device_ffi::Error> {
+        let this = ManuallyDrop::new(self);
+
+        unsafe {
+            if this.ok {
+                return Ok(T::decode(this.data));
+            }
+
+            let error = ptr::read(this.data.cast::<device_ffi::Error>());
+            libc::free(this.data.cast_mut());
+            Err(error)
+        }
+    }
+}
+
+impl Drop for Response {
+    fn drop(&mut self) {
+        // only reached if nothing was waiting on the response, so the output
+        // (if any) is plain data
+        if !self.ok && !self.data.is_null() {
+            unsafe { drop(ptr::read(self.data.cast::<device_ffi::Error>())) }
+        }
+
+        unsafe { libc::free(self.data.cast_mut()) }
+    }
 }
 
 unsafe impl Send for Response {}
@@ -48,6 +148,8 @@ unsafe impl Sync for Response {}
 #[cfg(feature = "build")]
 pub(crate) fn __ffi_inventory(builder: InventoryBuilder) -> InventoryBuilder {
     builder
+        .register(extra_type!(AbiVersion))
+        .register(extra_type!(CanStatus))
         .register(extra_type!(DeviceDatas))
         .register(extra_type!(FFIData))
         .register(extra_type!(Response))
@@ -1,4 +1,4 @@
-#![feature(error_generic_member_access, downcast_unchecked)]
+#![feature(error_generic_member_access, downcast_unchecked, linkage)]
 #![warn(clippy::pedantic)]
 #![allow(
     clippy::arc_with_non_send_sync,
@@ -10,6 +10,8 @@
     clippy::cast_possible_truncation
 )]
 
+extern crate async_lock;
+#[cfg(feature = "async-std")]
 extern crate async_std;
 extern crate interoptopus;
 extern crate libc;
@@ -20,31 +22,77 @@ extern crate uom;
 
 pub mod control;
 pub mod device;
+mod error;
+pub mod executor;
 mod ffi;
 pub mod prelude;
+mod runtime;
+pub mod schedule;
 
-use std::{thread, time::Duration};
+use std::{process, thread, time::Duration};
 
-use async_std::task;
-use device::ctx::DeviceContext;
+pub use error::{Error, ErrorKind, Result};
+use futures::future;
 use prelude::*;
+pub use runtime::{Runtime, Scoped};
 
 async fn main() {
     println!("Hello World!");
-    task::sleep(Duration::from_secs(0)).await;
+    executor::sleep(Duration::from_secs(0)).await;
 }
 
+/// The bindings this library was built against
 #[allow(unused)]
 #[ffi_function(namespace = "ffi")]
-extern "C" fn start_thread() {
-    // spawn the main thread
-    thread::spawn(|| task::block_on(main()));
+extern "C" fn abi_version() -> ffi::AbiVersion {
+    ffi::AbiVersion::CURRENT
 }
 
+/// Start ferrobot. `robot` is the version from the robot's generated header,
+/// and must match the library's, or the struct layouts on either side could
+/// silently disagree.
+#[allow(unused)]
+#[ffi_function(namespace = "ffi")]
+extern "C" fn start_thread(robot: ffi::AbiVersion) {
+    let library = ffi::AbiVersion::CURRENT;
+
+    if robot != library {
+        let message = format!(
+            "ABI mismatch: the robot was built against bindings {robot}, but the library was \
+             built from {library}. Run `cargo xtask regenerate` and rebuild both."
+        );
+
+        error!("{message}");
+        // no logger may be set up this early, and the robot is about to abort
+        eprintln!("ferrobot: {message}");
+        process::abort();
+    }
+
+    // spawn the main thread. It keeps running the executor once `main`
+    // returns, as the ticks the robot supplies are spawned onto it
+    thread::spawn(|| {
+        Runtime::global().block_on(async {
+            main().await;
+            future::pending::<()>().await;
+        });
+    });
+}
+
+/// Supply data for every device, then tick the global runtime
 #[allow(static_mut_refs, clippy::await_holding_lock, unused)]
 #[ffi_function(namespace = "ffi")]
 fn supply(context: ffi::FFIData) {
-    task::spawn(DeviceContext::instance().replace(context.devices));
+    let runtime = Runtime::global();
+    let ctx = Arc::clone(runtime.devices());
+
+    // commands are handled by the tick, off the robot's main loop, so a slow
+    // vendor call delays the tick, and the main loop only waits for it when
+    // collecting data
+    runtime.clone().spawn(async move {
+        ctx.update_can(context.can, context.timestamp).await;
+        ctx.update(context.devices, context.timestamp).await;
+        runtime.tick(context.timestamp).await;
+    });
 }
 
 #[cfg(feature = "build")]
@@ -62,11 +110,33 @@ pub mod build {
 
         builder
             .register(function!(crate::supply))
+            .register(function!(crate::abi_version))
             .register(function!(crate::start_thread))
             .validate()
             .build()
     }
 
+    /// The ABI constants for the robot to hand to `start_thread`, as a header
+    /// to write alongside the generated bindings
+    #[must_use]
+    pub fn __ffi_abi_header() -> String {
+        let crate::ffi::AbiVersion {
+            version,
+            inventory_hash,
+        } = crate::ffi::AbiVersion::CURRENT;
+
+        format!(
+            "// Generated by ferrobot-builder, do not edit\n\
+             #pragma once\n\n\
+             #include <cstdint>\n\n\
+             namespace ffi\n\
+             {{\n\
+             \tconstexpr uint32_t ABI_VERSION = {version};\n\
+             \tconstexpr uint64_t INVENTORY_HASH = {inventory_hash:#018x}ULL;\n\
+             }}\n"
+        )
+    }
+
     #[must_use]
     pub fn __ffi_interop() -> Interop {
         InteropBuilder::new()
@@ -0,0 +1,337 @@
+use std::{
+    cell::RefCell,
+    pin::{Pin, pin},
+    sync::LazyLock,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::future;
+
+use crate::{
+    control::{command::CommandScheduler, event::Emitter},
+    device::ctx::DeviceContext,
+    executor::{self, Executor, JoinHandle},
+    prelude::*,
+    schedule::Scheduler,
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Runtime>> = const { RefCell::new(None) };
+}
+
+/// Everything a robot runs on: its registered devices, the callbacks
+/// registered on events, its periodic functions and commands, and the tasks
+/// it spawns.
+/// Runtimes are isolated from each other, so several robots can run in one
+/// process, e.g. in tests.
+///
+/// Devices and events use the current runtime, which is set for the duration
+/// of [`Runtime::block_on`] or [`Runtime::scope`], and inherited by tasks
+/// spawned with [`Runtime::spawn`]. Outside of any runtime, the global
+/// runtime is used, which is the one the robot supplies data to.
+#[derive(Clone)]
+pub struct Runtime {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    devices: Arc<DeviceContext>,
+    emitter: Arc<Emitter>,
+    executor: Arc<dyn Executor>,
+    scheduler: Arc<Scheduler>,
+    commands: Arc<CommandScheduler>,
+}
+
+impl Runtime {
+    /// A runtime on the executor picked by the enabled features
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_executor(executor::default())
+    }
+
+    #[must_use]
+    pub fn with_executor(executor: impl Executor) -> Self {
+        Self::from_executor(Arc::new(executor))
+    }
+
+    fn from_executor(executor: Arc<dyn Executor>) -> Self {
+        Self::from_parts(executor, DeviceContext::new())
+    }
+
+    /// A runtime whose commands are handed to `handler` instead of the robot
+    #[cfg(test)]
+    pub(crate) fn with_handler(handler: crate::device::ctx::Handler) -> Self {
+        Self::from_parts(executor::default(), DeviceContext::with_handler(handler))
+    }
+
+    fn from_parts(executor: Arc<dyn Executor>, devices: Arc<DeviceContext>) -> Self {
+        let scheduler = Arc::new(Scheduler::new());
+        let commands = Arc::new(CommandScheduler::new());
+
+        // commands run every tick, before any other periodic functions
+        let tick = Arc::clone(&commands);
+        scheduler.add(
+            Some("CommandScheduler".into()),
+            Duration::ZERO,
+            Box::new(move || {
+                let commands = Arc::clone(&tick);
+                Box::pin(async move { commands.tick().await })
+            }),
+        );
+
+        Self {
+            inner: Arc::new(Inner {
+                devices,
+                emitter: Arc::new(Emitter::new()),
+                executor,
+                scheduler,
+                commands,
+            }),
+        }
+    }
+
+    /// The runtime the robot supplies data to and handles commands from
+    #[must_use]
+    pub fn global() -> Self {
+        static GLOBAL: LazyLock<Runtime> = LazyLock::new(Runtime::new);
+        GLOBAL.clone()
+    }
+
+    /// The runtime of the running task, or the global runtime
+    #[must_use]
+    pub fn current() -> Self {
+        CURRENT
+            .with_borrow(Clone::clone)
+            .unwrap_or_else(Self::global)
+    }
+
+    /// Run `future` with this as the current runtime
+    pub fn scope<F: Future>(&self, future: F) -> Scoped<F> {
+        Scoped {
+            runtime: self.clone(),
+            future,
+        }
+    }
+
+    /// Spawn a task with this as its current runtime
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, task) = JoinHandle::new(self.scope(future));
+        self.inner.executor.spawn(Box::pin(task));
+        handle
+    }
+
+    /// Block the thread on `future`, with this as the current runtime
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut output = None;
+
+        {
+            let future = pin!(async {
+                output = Some(self.scope(future).await);
+            });
+            self.inner.executor.block_on(future);
+        }
+
+        output.expect("Executor returned before the future completed")
+    }
+
+    /// Run one robot tick: periodic functions and commands due at
+    /// `timestamp` run, and the device commands they issue are handed to the
+    /// robot whenever the tick waits on them, then once more when it's done,
+    /// along with any issued since the last tick. `timestamp` is the FPGA
+    /// time of the tick, in microseconds. On an executor whose timers follow
+    /// robot time, timers due by `timestamp` fire first.
+    ///
+    /// The robot ticks the global runtime each time it supplies data. Other
+    /// runtimes are only ticked when this is called, e.g. by a test.
+    pub async fn tick(&self, timestamp: u64) {
+        let devices = &self.inner.devices;
+        self.inner.executor.advance(timestamp);
+
+        let mut tick = pin!(self.scope(self.inner.scheduler.tick(timestamp)));
+
+        future::poll_fn(|cx| {
+            loop {
+                if tick.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(());
+                }
+
+                // the tick may be waiting on commands it issued, which would
+                // otherwise only be handled on the next tick. Flushing wakes
+                // whatever waits on them, so poll again.
+                if !devices.flush() {
+                    return Poll::Pending;
+                }
+            }
+        })
+        .await;
+
+        devices.flush();
+    }
+
+    /// Wait for `duration` on this runtime's executor
+    pub async fn sleep(&self, duration: Duration) {
+        self.inner.executor.sleep(duration).await;
+    }
+
+    pub(crate) fn devices(&self) -> &Arc<DeviceContext> {
+        &self.inner.devices
+    }
+
+    pub(crate) fn emitter(&self) -> &Arc<Emitter> {
+        &self.inner.emitter
+    }
+
+    pub(crate) fn scheduler(&self) -> &Arc<Scheduler> {
+        &self.inner.scheduler
+    }
+
+    pub(crate) fn commands(&self) -> &Arc<CommandScheduler> {
+        &self.inner.commands
+    }
+
+    /// Make this the current runtime until the guard is dropped
+    fn enter(&self) -> Guard {
+        Guard {
+            previous: CURRENT.replace(Some(self.clone())),
+        }
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Guard {
+    previous: Option<Runtime>,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        CURRENT.set(self.previous.take());
+    }
+}
+
+/// A future run with a runtime as the current runtime. Created by
+/// [`Runtime::scope`].
+pub struct Scoped<F> {
+    runtime: Runtime,
+    future: F,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // `future` is structurally pinned, and never moved out of `self`
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        let _guard = this.runtime.enter();
+        future.poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use futures::FutureExt;
+
+    use super::*;
+    use crate::{ErrorKind, device::io::DigitalOutput, ffi::Response, schedule};
+
+    /// A runtime whose commands succeed, counting how many were handled
+    fn runtime() -> (Runtime, Arc<AtomicUsize>) {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&handled);
+        let runtime = Runtime::with_handler(Arc::new(move |commands| {
+            count.fetch_add(commands.len(), Ordering::AcqRel);
+            Some(commands.iter().map(|_| Response::ok()).collect())
+        }));
+
+        (runtime, handled)
+    }
+
+    /// Poll `future`, ticking `runtime` each time it stalls, until it completes
+    async fn drive<F: Future>(runtime: &Runtime, future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut timestamp = 0;
+
+        loop {
+            if let Some(output) = future.as_mut().now_or_never() {
+                return output;
+            }
+
+            runtime.tick(timestamp).await;
+            timestamp += 20_000;
+        }
+    }
+
+    #[test]
+    fn runtimes_are_isolated() {
+        let (a, a_handled) = runtime();
+        let (b, b_handled) = runtime();
+        let a_runs = Arc::new(AtomicUsize::new(0));
+        let runs = Arc::clone(&a_runs);
+
+        a.block_on(async {
+            schedule::periodic(Duration::ZERO, move || {
+                runs.fetch_add(1, Ordering::AcqRel);
+                async {}
+            });
+
+            let output = drive(&a, DigitalOutput::new(0)).await.unwrap();
+            let duplicate = drive(&a, DigitalOutput::new(0)).await.unwrap_err();
+            assert_eq!(duplicate.kind(), ErrorKind::AlreadyExists);
+
+            drop(output);
+        });
+
+        let ticked = a_runs.load(Ordering::Acquire);
+        assert!(ticked > 0);
+        assert_eq!(a_handled.load(Ordering::Acquire), 1);
+
+        // the same channel is free on the other runtime, and ticking it runs
+        // none of the first runtime's functions
+        b.block_on(async {
+            let output = drive(&b, DigitalOutput::new(0)).await.unwrap();
+            drop(output);
+        });
+
+        assert_eq!(a_runs.load(Ordering::Acquire), ticked);
+        assert_eq!(b_handled.load(Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn commands_issued_in_a_tick_are_handled_in_it() {
+        let (runtime, handled) = runtime();
+        let set = Arc::new(AtomicBool::new(false));
+        let done = Arc::clone(&set);
+
+        runtime.block_on(async {
+            let output = drive(&runtime, DigitalOutput::new(0)).await.unwrap();
+
+            schedule::periodic(Duration::ZERO, move || {
+                let output = Arc::clone(&output);
+                let done = Arc::clone(&done);
+
+                async move {
+                    output.set(true).await.unwrap();
+                    done.store(true, Ordering::Release);
+                }
+            });
+
+            runtime.tick(1_000_000).await;
+        });
+
+        assert!(set.load(Ordering::Acquire));
+        assert_eq!(handled.load(Ordering::Acquire), 2);
+    }
+}
@@ -0,0 +1,341 @@
+use std::{
+    fmt::Write,
+    sync::{
+        Mutex, PoisonError, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{Runtime, prelude::*};
+
+/// Default time the robot loop is expected to take, matching the robot's
+/// 20ms tick
+const DEFAULT_LOOP_PERIOD: Duration = Duration::from_millis(20);
+
+pub(crate) type Run = Box<dyn (Fn() -> BoxFuture<'static, ()>) + Send + Sync>;
+
+/// How long a function, or the whole loop, took to run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimingStats {
+    pub runs: u64,
+    /// Runs that took longer than their period, or than the loop period for
+    /// functions due every tick
+    pub overruns: u64,
+    pub last: Duration,
+    pub max: Duration,
+    pub total: Duration,
+}
+
+impl TimingStats {
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        if self.runs == 0 {
+            return Duration::ZERO;
+        }
+
+        self.total / self.runs as u32
+    }
+
+    fn record(&mut self, elapsed: Duration, period: Duration) {
+        self.runs += 1;
+        self.last = elapsed;
+        self.max = self.max.max(elapsed);
+        self.total += elapsed;
+
+        if elapsed > period {
+            self.overruns += 1;
+        }
+    }
+}
+
+pub(crate) struct Entry {
+    name: Mutex<String>,
+    period: Duration,
+    /// FPGA time the function is next due, in microseconds, or `None` if it
+    /// hasn't run yet
+    next_run: Mutex<Option<u64>>,
+    run: Run,
+    stats: Mutex<TimingStats>,
+}
+
+impl Entry {
+    /// Whether the function is due at `timestamp`, scheduling the next run if
+    /// it is. Runs missed by falling behind are skipped rather than caught up.
+    fn due(&self, timestamp: u64) -> bool {
+        let period = self.period.as_micros() as u64;
+        let mut next_run = self.next_run.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match *next_run {
+            Some(next) if timestamp < next => false,
+            Some(next) => {
+                let next = next + period;
+                *next_run = Some(if next <= timestamp {
+                    timestamp + period
+                } else {
+                    next
+                });
+                true
+            }
+            None => {
+                *next_run = Some(timestamp + period);
+                true
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Runs periodic functions on each robot tick, in the order they were
+/// registered
+pub(crate) struct Scheduler {
+    entries: Mutex<Vec<Arc<Entry>>>,
+    running: AtomicBool,
+    /// Expected time of the whole loop, in microseconds
+    loop_period: AtomicU64,
+    /// FPGA time of the latest tick, in microseconds
+    now: AtomicU64,
+    stats: Mutex<TimingStats>,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+            running: AtomicBool::new(false),
+            loop_period: AtomicU64::new(DEFAULT_LOOP_PERIOD.as_micros() as u64),
+            now: AtomicU64::new(0),
+            stats: Mutex::new(TimingStats::default()),
+        }
+    }
+
+    /// Run every function due at `timestamp`, the FPGA time of the tick in
+    /// microseconds. A tick that arrives while the previous one is still
+    /// running is skipped, and counted as an overrun.
+    pub(crate) async fn tick(&self, timestamp: u64) {
+        let loop_period = self.loop_period();
+
+        if self.running.swap(true, Ordering::AcqRel) {
+            let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+            stats.overruns += 1;
+            warn!("Loop overrun: skipped a tick, as the previous one is still running");
+            return;
+        }
+
+        // cleared even if a function panics or the tick is cancelled, so one
+        // bad tick doesn't skip every later one
+        let _running = Running(&self.running);
+        self.now.store(timestamp, Ordering::Release);
+
+        let entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let start = Instant::now();
+        let mut epochs = Vec::new();
+
+        for entry in entries.iter().filter(|entry| entry.due(timestamp)) {
+            let started = Instant::now();
+            (entry.run)().await;
+            let elapsed = started.elapsed();
+
+            // a function due more often than the loop runs once per tick
+            entry
+                .stats
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record(elapsed, entry.period.max(loop_period));
+            epochs.push((entry, elapsed));
+        }
+
+        let elapsed = start.elapsed();
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(elapsed, loop_period);
+
+        if elapsed > loop_period {
+            let mut message = format!("Loop time of {loop_period:?} overrun, took {elapsed:?}");
+
+            for (entry, elapsed) in epochs {
+                let _ = write!(message, "\n\t{}: {elapsed:?}", entry.name());
+            }
+
+            warn!("{message}");
+        }
+    }
+
+    /// How long the periodic functions of a tick are expected to take
+    pub(crate) fn loop_period(&self) -> Duration {
+        Duration::from_micros(self.loop_period.load(Ordering::Acquire))
+    }
+
+    /// FPGA time of the latest tick, in microseconds. Robot time only advances
+    /// between ticks, so everything run in a tick sees the same time.
+    pub(crate) fn now(&self) -> u64 {
+        self.now.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn add(&self, name: Option<String>, period: Duration, run: Run) -> Arc<Entry> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = Arc::new(Entry {
+            name: Mutex::new(name.unwrap_or_else(|| format!("periodic #{}", entries.len()))),
+            period,
+            next_run: Mutex::new(None),
+            run,
+            stats: Mutex::new(TimingStats::default()),
+        });
+
+        entries.push(Arc::clone(&entry));
+        entry
+    }
+
+    fn remove(&self, entry: &Arc<Entry>) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|other| !Arc::ptr_eq(other, entry));
+    }
+}
+
+/// Clears the scheduler's `running` flag when dropped
+struct Running<'a>(&'a AtomicBool);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// A function registered with [`periodic`]. Dropping the handle leaves the
+/// function running.
+pub struct Periodic {
+    entry: Arc<Entry>,
+    scheduler: Weak<Scheduler>,
+}
+
+impl Periodic {
+    /// Set the name the function is logged with on a loop overrun
+    pub fn set_name(&self, name: impl Into<String>) {
+        *self
+            .entry
+            .name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = name.into();
+    }
+
+    #[must_use]
+    pub fn period(&self) -> Duration {
+        self.entry.period
+    }
+
+    /// How long the function has taken to run
+    #[must_use]
+    pub fn stats(&self) -> TimingStats {
+        *self
+            .entry
+            .stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stop running the function
+    pub fn cancel(self) {
+        if let Some(scheduler) = self.scheduler.upgrade() {
+            scheduler.remove(&self.entry);
+        }
+    }
+}
+
+/// Run `f` every `period` of robot time, on the robot tick it falls due, after
+/// the tick's data has been supplied. Functions run one at a time, in the
+/// order they were registered, so a period shorter than the robot loop runs
+/// once per tick.
+pub fn periodic<F, Fut>(period: Duration, f: F) -> Periodic
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let scheduler = Arc::clone(Runtime::current().scheduler());
+    let entry = scheduler.add(None, period, Box::new(move || Box::pin(f())));
+
+    Periodic {
+        entry,
+        scheduler: Arc::downgrade(&scheduler),
+    }
+}
+
+/// Set how long the periodic functions of a tick are expected to take in
+/// total, before an overrun is logged. Defaults to 20ms.
+pub fn set_loop_period(period: Duration) {
+    Runtime::current()
+        .scheduler()
+        .loop_period
+        .store(period.as_micros() as u64, Ordering::Release);
+}
+
+/// How long each tick's periodic functions have taken to run in total
+#[must_use]
+pub fn loop_stats() -> TimingStats {
+    *Runtime::current()
+        .scheduler()
+        .stats
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::atomic::AtomicU32,
+    };
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn panicking_function_does_not_skip_later_ticks() {
+        let scheduler = Scheduler::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&runs);
+
+        scheduler.add(
+            None,
+            Duration::ZERO,
+            Box::new(move || {
+                let runs = counter.fetch_add(1, Ordering::AcqRel) + 1;
+                Box::pin(async move { assert!(runs > 1, "first run panics") })
+            }),
+        );
+
+        let first = panic::catch_unwind(AssertUnwindSafe(|| block_on(scheduler.tick(0))));
+        assert!(first.is_err());
+
+        block_on(scheduler.tick(20_000));
+        assert_eq!(runs.load(Ordering::Acquire), 2);
+    }
+
+    #[test]
+    fn every_tick_function_is_timed_against_the_loop() {
+        let scheduler = Scheduler::new();
+        let entry = scheduler.add(None, Duration::ZERO, Box::new(|| Box::pin(async {})));
+
+        block_on(scheduler.tick(0));
+        block_on(scheduler.tick(20_000));
+
+        let stats = *entry.stats.lock().unwrap();
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.overruns, 0);
+    }
+}